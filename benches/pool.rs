@@ -0,0 +1,56 @@
+//! Throughput of buffered versus unbuffered `Tyche` instances.
+//!
+//! Run with `cargo bench`.
+
+use athena::rng_api::RngApi;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use tyche::Tyche;
+
+const ITERATIONS: usize = 1_000_000;
+
+fn time_u8<S: tyche::EntropySource>(mut tyche: Tyche<S>) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(tyche.random_u8().unwrap());
+    }
+    start.elapsed()
+}
+
+fn time_u64<S: tyche::EntropySource>(mut tyche: Tyche<S>) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(tyche.random_u64().unwrap());
+    }
+    start.elapsed()
+}
+
+fn report(name: &str, unbuffered: Duration, buffered: Duration) {
+    let per_sec = |d: Duration| ITERATIONS as f64 / d.as_secs_f64();
+    println!(
+        "{name:<22} unbuffered {:>12.0}/s   buffered {:>12.0}/s   speedup {:>6.1}x",
+        per_sec(unbuffered),
+        per_sec(buffered),
+        unbuffered.as_secs_f64() / buffered.as_secs_f64()
+    );
+}
+
+fn main() {
+    println!("{ITERATIONS} calls each");
+
+    let unbuffered = time_u8(Tyche::new().unwrap().with_buffer_size(0));
+    let buffered = time_u8(Tyche::new().unwrap());
+    report("getrandom random_u8", unbuffered, buffered);
+
+    let unbuffered = time_u64(Tyche::new().unwrap().with_buffer_size(0));
+    let buffered = time_u64(Tyche::new().unwrap());
+    report("getrandom random_u64", unbuffered, buffered);
+
+    let unbuffered = time_u8(Tyche::from_urandom().unwrap().with_buffer_size(0));
+    let buffered = time_u8(Tyche::from_urandom().unwrap());
+    report("urandom random_u8", unbuffered, buffered);
+
+    let unbuffered = time_u8(Tyche::new().unwrap().with_buffer_size(0));
+    let buffered = time_u8(Tyche::new().unwrap().with_buffer_size(4096));
+    report("4 KiB buffer random_u8", unbuffered, buffered);
+}
@@ -0,0 +1,69 @@
+//! Throughput of `ReseedingRng` and the seedable generators in `tyche::rngs` against `Tyche`.
+//!
+//! Run with `cargo bench`.
+
+use athena::rng_api::RngApi;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use tyche::rngs::{
+    ChaCha8Rng, ChaCha20Rng, Pcg32, Pcg64, SeedableRng, SplitMix64, Xoshiro128PlusPlus,
+    Xoshiro256StarStar,
+};
+use tyche::{ReseedingRng, Tyche};
+
+const ITERATIONS: usize = 10_000_000;
+
+fn time_u64<R: RngApi>(mut rng: R) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(rng.random_u64().unwrap());
+    }
+    start.elapsed()
+}
+
+fn report(name: &str, elapsed: Duration, baseline: Duration) {
+    let per_sec = ITERATIONS as f64 / elapsed.as_secs_f64();
+    println!(
+        "{name:<20} {per_sec:>14.0}/s   {:>7.1}x Tyche",
+        baseline.as_secs_f64() / elapsed.as_secs_f64()
+    );
+}
+
+fn main() {
+    println!("{ITERATIONS} random_u64 calls each");
+
+    let baseline = time_u64(Tyche::new().unwrap());
+    report("Tyche", baseline, baseline);
+    report(
+        "ReseedingRng",
+        time_u64(ReseedingRng::new().unwrap()),
+        baseline,
+    );
+    report(
+        "ChaCha20Rng",
+        time_u64(ChaCha20Rng::seed_from_u64(1)),
+        baseline,
+    );
+    report(
+        "ChaCha8Rng",
+        time_u64(ChaCha8Rng::seed_from_u64(1)),
+        baseline,
+    );
+    report(
+        "Xoshiro256StarStar",
+        time_u64(Xoshiro256StarStar::seed_from_u64(1)),
+        baseline,
+    );
+    report(
+        "Xoshiro128PlusPlus",
+        time_u64(Xoshiro128PlusPlus::seed_from_u64(1)),
+        baseline,
+    );
+    report("Pcg32", time_u64(Pcg32::seed_from_u64(1)), baseline);
+    report("Pcg64", time_u64(Pcg64::seed_from_u64(1)), baseline);
+    report(
+        "SplitMix64",
+        time_u64(SplitMix64::seed_from_u64(1)),
+        baseline,
+    );
+}
@@ -0,0 +1,109 @@
+//! The Bernoulli distribution, computed exactly.
+
+use super::Distribution;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Why a [`Bernoulli`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BernoulliError {
+    /// The probability is NaN or outside `[0, 1]`.
+    BadProbability,
+}
+
+impl Display for BernoulliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            BernoulliError::BadProbability => write!(f, "The probability must be in [0, 1]!"),
+        }
+    }
+}
+
+impl Error for BernoulliError {}
+
+impl From<BernoulliError> for RngError {
+    fn from(e: BernoulliError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// The Bernoulli distribution, `true` with probability `p`.
+///
+/// The result is exact for every `f64` value of `p`, not rounded to a multiple of `2^-53` or
+/// `2^-64`: a uniform number `U` in `[0, 1)` is drawn 64 bits at a time and compared against
+/// the binary expansion of `p`, and `true` is returned iff `U < p`. Every `f64` has a finite
+/// binary expansion, so the comparison almost always ends with the first 64 bit chunk and
+/// needs more only when the drawn bits equal `p`'s.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bernoulli {
+    p: f64,
+    /// `p = mantissa * 2^exponent`, with `exponent < 0` unless `p == 1`.
+    mantissa: u64,
+    exponent: i32,
+}
+
+impl Bernoulli {
+    /// Create a Bernoulli distribution that is `true` with probability `p`.
+    ///
+    /// ## Errors
+    /// Returns `BernoulliError` if `p` is NaN or outside `[0, 1]`.
+    pub fn new(p: f64) -> Result<Self, BernoulliError> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(BernoulliError::BadProbability);
+        }
+        let bits = p.to_bits();
+        let biased = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, exponent) = if biased == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | (1 << 52), biased - 1075)
+        };
+        Ok(Bernoulli {
+            p,
+            mantissa,
+            exponent,
+        })
+    }
+
+    /// The probability of `true`.
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+
+    /// Bits `64 * chunk .. 64 * (chunk + 1)` after the binary point of `p`, as an integer.
+    fn chunk(&self, chunk: i32) -> u64 {
+        let shift = self.exponent + 64 * (chunk + 1);
+        if shift <= -64 || shift >= 64 {
+            0
+        } else if shift < 0 {
+            self.mantissa >> -shift
+        } else {
+            // Bits shifted past 64 are before this chunk
+            ((self.mantissa as u128) << shift) as u64
+        }
+    }
+}
+
+impl Distribution<bool> for Bernoulli {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<bool> {
+        if self.p == 1.0 {
+            return Ok(true);
+        }
+        if self.p == 0.0 {
+            return Ok(false);
+        }
+        let mut chunk = 0;
+        loop {
+            let digits = self.chunk(chunk);
+            let u = rng.random_u64()?;
+            if u != digits {
+                return Ok(u < digits);
+            }
+            // Equal so far, the next 64 bits decide. Past the end of `p`'s expansion every
+            // chunk is 0, so this ends with the first non-zero draw.
+            chunk = chunk.saturating_add(1);
+        }
+    }
+}
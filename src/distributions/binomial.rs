@@ -0,0 +1,242 @@
+//! The binomial distribution.
+
+use super::Distribution;
+use crate::RngApiExt;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Why a [`Binomial`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinomialError {
+    /// The probability is NaN or outside `[0, 1]`.
+    BadProbability,
+    /// More than `i64::MAX` trials.
+    TooManyTrials,
+}
+
+impl Display for BinomialError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            BinomialError::BadProbability => write!(f, "The probability must be in [0, 1]!"),
+            BinomialError::TooManyTrials => {
+                write!(f, "The number of trials must not exceed {}!", i64::MAX)
+            }
+        }
+    }
+}
+
+impl Error for BinomialError {}
+
+impl From<BinomialError> for RngError {
+    fn from(e: BinomialError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// Below this mean, inversion is faster than BTPE.
+const INVERSION_THRESHOLD: f64 = 10.0;
+/// Inversion restarts past this many successes, which only guards against rounding drift
+/// since the mean is below 10.
+const INVERSION_MAX_X: u64 = 110;
+/// Up to this distance from the mode BTPE evaluates the density directly.
+const SQUEEZE_THRESHOLD: i64 = 20;
+
+/// The binomial distribution `Binomial(n, p)`, the number of successes in `n` independent
+/// trials that each succeed with probability `p`.
+///
+/// Small means are sampled by inversion, large ones with the BTPE algorithm of
+/// Kachitvichyanukul & Schmeiser, "Binomial Random Variate Generation", 1988, whose cost does
+/// not grow with `n`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Binomial {
+    n: u64,
+    p: f64,
+}
+
+impl Binomial {
+    /// Create a binomial distribution with `n` trials and success probability `p`.
+    ///
+    /// ## Errors
+    /// Returns `BinomialError` if `p` is NaN or outside `[0, 1]`, or `n` exceeds `i64::MAX`.
+    pub fn new(n: u64, p: f64) -> Result<Self, BinomialError> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(BinomialError::BadProbability);
+        }
+        if n > i64::MAX as u64 {
+            return Err(BinomialError::TooManyTrials);
+        }
+        Ok(Binomial { n, p })
+    }
+
+    /// The number of trials.
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    /// The success probability of every trial.
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+}
+
+impl Distribution<u64> for Binomial {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<u64> {
+        if self.p == 0.0 || self.n == 0 {
+            return Ok(0);
+        }
+        if self.p == 1.0 {
+            return Ok(self.n);
+        }
+        // Sample the rarer outcome and flip back, both algorithms need p <= 0.5
+        let flipped = self.p > 0.5;
+        let p = if flipped { 1.0 - self.p } else { self.p };
+        let successes = if self.n as f64 * p < INVERSION_THRESHOLD {
+            inversion(rng, self.n, p)?
+        } else {
+            btpe(rng, self.n as i64, p)? as u64
+        };
+        Ok(if flipped {
+            self.n - successes
+        } else {
+            successes
+        })
+    }
+}
+
+/// Walk the cumulative distribution from 0 upwards.
+fn inversion<R: RngApi + ?Sized>(rng: &mut R, n: u64, p: f64) -> RngResult<u64> {
+    let q = 1.0 - p;
+    let s = p / q;
+    let a = (n as f64 + 1.0) * s;
+    let r0 = q.powf(n as f64);
+    'restart: loop {
+        let mut r = r0;
+        let mut u = rng.random_unit_f64()?;
+        let mut x = 0;
+        while u > r {
+            u -= r;
+            x += 1;
+            if x > INVERSION_MAX_X {
+                continue 'restart;
+            }
+            r *= a / x as f64 - s;
+        }
+        return Ok(x);
+    }
+}
+
+/// Stirling's series correction term for `ln(a!)`, used in BTPE's final acceptance test.
+fn stirling(a: f64) -> f64 {
+    let a2 = a * a;
+    (13860.0 - (462.0 - (132.0 - (99.0 - 140.0 / a2) / a2) / a2) / a2) / a / 166_320.0
+}
+
+/// BTPE: triangle, parallelogram and exponential tails over the density, with `p <= 0.5`.
+fn btpe<R: RngApi + ?Sized>(rng: &mut R, n: i64, p: f64) -> RngResult<i64> {
+    let nf = n as f64;
+    let q = 1.0 - p;
+    let npq = nf * p * q;
+    let f_m = nf * p + p;
+    let m = f_m.floor() as i64;
+    let mf = m as f64;
+    // The triangle's half width and the region boundaries
+    let p1 = (2.195 * npq.sqrt() - 4.6 * q).floor() + 0.5;
+    let x_m = mf + 0.5;
+    let x_l = x_m - p1;
+    let x_r = x_m + p1;
+    // The parallelogram's slope
+    let c = 0.134 + 20.5 / (15.3 + mf);
+    let lambda = |a: f64| a * (1.0 + 0.5 * a);
+    let lambda_l = lambda((f_m - x_l) / (f_m - x_l * p));
+    let lambda_r = lambda((x_r - f_m) / (x_r * q));
+    let p2 = p1 * (1.0 + 2.0 * c);
+    let p3 = p2 + c / lambda_l;
+    let p4 = p3 + c / lambda_r;
+
+    loop {
+        let u = rng.random_unit_f64()? * p4;
+        let mut v = rng.random_unit_f64_open_closed()?;
+        let y;
+        if u <= p1 {
+            // Triangle, accepted outright
+            return Ok((x_m - p1 * v + u).floor() as i64);
+        } else if u <= p2 {
+            // Parallelogram
+            let x = x_l + (u - p1) / c;
+            v = v * c + 1.0 - (x - x_m).abs() / p1;
+            if v > 1.0 {
+                continue;
+            }
+            y = x.floor() as i64;
+        } else if u <= p3 {
+            // Left exponential tail
+            y = (x_l + v.ln() / lambda_l).floor() as i64;
+            if y < 0 {
+                continue;
+            }
+            v *= (u - p2) * lambda_l;
+        } else {
+            // Right exponential tail
+            y = (x_r - v.ln() / lambda_r).floor() as i64;
+            if y > n {
+                continue;
+            }
+            v *= (u - p3) * lambda_r;
+        }
+
+        let k = (y - m).abs();
+        if k <= SQUEEZE_THRESHOLD || k as f64 >= 0.5 * npq - 1.0 {
+            // Evaluate f(y) / f(m) by the recurrence of the density
+            let s = p / q;
+            let a = s * (nf + 1.0);
+            let mut f = 1.0;
+            match y.cmp(&m) {
+                Ordering::Less => {
+                    for i in y + 1..=m {
+                        f /= a / i as f64 - s;
+                    }
+                }
+                Ordering::Greater => {
+                    for i in m + 1..=y {
+                        f *= a / i as f64 - s;
+                    }
+                }
+                Ordering::Equal => {}
+            }
+            if v <= f {
+                return Ok(y);
+            }
+            continue;
+        }
+
+        // Squeeze on ln(f(y) / f(m)) with a normal approximation
+        let kf = k as f64;
+        let rho = (kf / npq) * ((kf * (kf / 3.0 + 0.625) + 1.0 / 6.0) / npq + 0.5);
+        let t = -0.5 * kf * kf / npq;
+        let alpha = v.ln();
+        if alpha < t - rho {
+            return Ok(y);
+        }
+        if alpha > t + rho {
+            continue;
+        }
+
+        // Final test against Stirling's approximation of ln(f(y) / f(m))
+        let x1 = (y + 1) as f64;
+        let f1 = mf + 1.0;
+        let z = nf + 1.0 - mf;
+        let w = (n - y) as f64 + 1.0;
+        let bound = x_m * (f1 / x1).ln()
+            + (nf - mf + 0.5) * (z / w).ln()
+            + (y - m) as f64 * (w * p / (x1 * q)).ln()
+            + stirling(f1)
+            + stirling(z)
+            + stirling(x1)
+            + stirling(w);
+        if alpha <= bound {
+            return Ok(y);
+        }
+    }
+}
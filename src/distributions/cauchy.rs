@@ -0,0 +1,79 @@
+//! The Cauchy distribution.
+
+use super::{Distribution, Float};
+use crate::RngApiExt;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::error::Error;
+use std::f64::consts::PI;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Why a [`Cauchy`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CauchyError {
+    /// The median is NaN or infinite.
+    MedianNotFinite,
+    /// The scale is not a finite, positive number.
+    BadScale,
+}
+
+impl Display for CauchyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            CauchyError::MedianNotFinite => write!(f, "The median must be finite!"),
+            CauchyError::BadScale => write!(f, "The scale must be finite and positive!"),
+        }
+    }
+}
+
+impl Error for CauchyError {}
+
+impl From<CauchyError> for RngError {
+    fn from(e: CauchyError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// The Cauchy distribution with the given median and half width at half maximum `scale`.
+///
+/// Neither mean nor variance exist. Sampled by inversion, `median + scale * tan(π (U - 1/2))`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cauchy<F: Float> {
+    median: F,
+    scale: F,
+}
+
+impl<F: Float> Cauchy<F> {
+    /// Create a Cauchy distribution.
+    ///
+    /// ## Errors
+    /// Returns `CauchyError` if `median` is not finite, or `scale` is not finite and positive.
+    pub fn new(median: F, scale: F) -> Result<Self, CauchyError> {
+        if !median.to_f64().is_finite() {
+            return Err(CauchyError::MedianNotFinite);
+        }
+        let s = scale.to_f64();
+        if !(s.is_finite() && s > 0.0) {
+            return Err(CauchyError::BadScale);
+        }
+        Ok(Cauchy { median, scale })
+    }
+
+    /// The median.
+    pub fn median(&self) -> F {
+        self.median
+    }
+
+    /// The scale.
+    pub fn scale(&self) -> F {
+        self.scale
+    }
+}
+
+impl<F: Float> Distribution<F> for Cauchy<F> {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<F> {
+        // π / 2 is not exactly representable, so the result stays finite even for U = 0
+        let u = rng.random_unit_f64()?;
+        let x = (PI * (u - 0.5)).tan();
+        Ok(F::from_f64(self.median.to_f64() + self.scale.to_f64() * x))
+    }
+}
@@ -0,0 +1,134 @@
+//! The Dirichlet distribution and uniform points on the simplex.
+
+use super::Distribution;
+use super::exponential::standard_exp;
+use super::gamma::ln_standard_gamma;
+use super::unit::DimensionError;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Uniformly distributed points on the standard simplex: `dimension` non-negative coordinates
+/// adding up to 1.
+///
+/// Normalised exponential samples, which is the Dirichlet distribution with every alpha 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Simplex {
+    dimension: usize,
+}
+
+impl Simplex {
+    /// Create the distribution for the given number of coordinates.
+    ///
+    /// ## Errors
+    /// Returns `DimensionError` if `dimension` is 0.
+    pub fn new(dimension: usize) -> Result<Self, DimensionError> {
+        if dimension == 0 {
+            return Err(DimensionError::Zero);
+        }
+        Ok(Simplex { dimension })
+    }
+
+    /// The number of coordinates.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+}
+
+impl Distribution<Vec<f64>> for Simplex {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<Vec<f64>> {
+        let mut point = vec![0.0; self.dimension];
+        loop {
+            let mut sum = 0.0;
+            for x in point.iter_mut() {
+                *x = standard_exp(rng)?;
+                sum += *x;
+            }
+            // All exponentials are 0 with probability 2^-53n
+            if sum > 0.0 {
+                point.iter_mut().for_each(|x| *x /= sum);
+                return Ok(point);
+            }
+        }
+    }
+}
+
+/// Why a [`Dirichlet`] distribution could not be created.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DirichletError {
+    /// No alphas were given.
+    NoItem,
+    /// The alpha at `index` is not a finite, positive number.
+    BadAlpha { index: usize },
+}
+
+impl Display for DirichletError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            DirichletError::NoItem => write!(f, "No alphas were given!"),
+            DirichletError::BadAlpha { index } => {
+                write!(f, "Alpha {index} is not a finite, positive number!")
+            }
+        }
+    }
+}
+
+impl Error for DirichletError {}
+
+impl From<DirichletError> for RngError {
+    fn from(e: DirichletError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// The Dirichlet distribution over points of the simplex, with concentration parameters
+/// `alpha`; coordinate `i` has mean `alpha[i] / sum(alpha)`.
+///
+/// Normalised `Gamma(alpha[i], 1)` samples. They are combined through their logarithms, so
+/// tiny alphas, whose samples would underflow to 0, still give points on the simplex.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dirichlet {
+    alpha: Vec<f64>,
+}
+
+impl Dirichlet {
+    /// Create a Dirichlet distribution.
+    ///
+    /// ## Errors
+    /// Returns `DirichletError` if `alpha` is empty or an alpha is not finite and positive.
+    pub fn new(alpha: &[f64]) -> Result<Self, DirichletError> {
+        if alpha.is_empty() {
+            return Err(DirichletError::NoItem);
+        }
+        if let Some(index) = alpha.iter().position(|&a| !(a.is_finite() && a > 0.0)) {
+            return Err(DirichletError::BadAlpha { index });
+        }
+        Ok(Dirichlet {
+            alpha: alpha.to_vec(),
+        })
+    }
+
+    /// The concentration parameters.
+    pub fn alpha(&self) -> &[f64] {
+        &self.alpha
+    }
+}
+
+impl Distribution<Vec<f64>> for Dirichlet {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<Vec<f64>> {
+        let mut point = self
+            .alpha
+            .iter()
+            .map(|&a| ln_standard_gamma(rng, a))
+            .collect::<RngResult<Vec<f64>>>()?;
+        // Shift so the largest is e^0, the sum is then in [1, n] and cannot underflow
+        let max = point.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mut sum = 0.0;
+        for x in point.iter_mut() {
+            *x = (*x - max).exp();
+            sum += *x;
+        }
+        point.iter_mut().for_each(|x| *x /= sum);
+        Ok(point)
+    }
+}
@@ -0,0 +1,70 @@
+//! The exponential distribution.
+
+use super::{Distribution, Float};
+use crate::RngApiExt;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Why an [`Exp`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExpError {
+    /// The rate is not a finite, positive number.
+    BadLambda,
+}
+
+impl Display for ExpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ExpError::BadLambda => write!(f, "The rate must be finite and positive!"),
+        }
+    }
+}
+
+impl Error for ExpError {}
+
+impl From<ExpError> for RngError {
+    fn from(e: ExpError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// The exponential distribution `Exp(lambda)`, the waiting time between events that occur
+/// `lambda` times per unit of time on average.
+///
+/// Sampled by inversion, `-ln(U) / lambda`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Exp<F: Float> {
+    lambda: F,
+}
+
+impl<F: Float> Exp<F> {
+    /// Create an exponential distribution with rate `lambda` and mean `1 / lambda`.
+    ///
+    /// ## Errors
+    /// Returns `ExpError` if `lambda` is not finite and positive.
+    pub fn new(lambda: F) -> Result<Self, ExpError> {
+        let l = lambda.to_f64();
+        if !(l.is_finite() && l > 0.0) {
+            return Err(ExpError::BadLambda);
+        }
+        Ok(Exp { lambda })
+    }
+
+    /// The rate.
+    pub fn lambda(&self) -> F {
+        self.lambda
+    }
+}
+
+impl<F: Float> Distribution<F> for Exp<F> {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<F> {
+        Ok(F::from_f64(standard_exp(rng)? / self.lambda.to_f64()))
+    }
+}
+
+/// Draw from `Exp(1)`.
+pub(crate) fn standard_exp<R: RngApi + ?Sized>(rng: &mut R) -> RngResult<f64> {
+    // (0, 1] keeps the logarithm finite
+    Ok(-rng.random_unit_f64_open_closed()?.ln())
+}
@@ -0,0 +1,252 @@
+//! The gamma distribution and the distributions derived from it.
+
+use super::ziggurat::standard_normal;
+use super::{Distribution, Float};
+use crate::RngApiExt;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+fn positive_finite(x: f64) -> bool {
+    x.is_finite() && x > 0.0
+}
+
+/// Why a [`Gamma`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GammaError {
+    /// The shape is not a finite, positive number.
+    BadShape,
+    /// The scale is not a finite, positive number.
+    BadScale,
+}
+
+impl Display for GammaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            GammaError::BadShape => write!(f, "The shape must be finite and positive!"),
+            GammaError::BadScale => write!(f, "The scale must be finite and positive!"),
+        }
+    }
+}
+
+impl Error for GammaError {}
+
+impl From<GammaError> for RngError {
+    fn from(e: GammaError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// The gamma distribution `Gamma(shape, scale)`, with mean `shape * scale`.
+///
+/// Sampled with the method of Marsaglia & Tsang, "A Simple Method for Generating Gamma
+/// Variables", 2000: a cubed, shifted normal sample accepted by a squeeze that almost never
+/// needs a logarithm. Shapes below 1 are boosted to `shape + 1` and scaled back down by
+/// `U^(1 / shape)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gamma<F: Float> {
+    shape: F,
+    scale: F,
+}
+
+impl<F: Float> Gamma<F> {
+    /// Create a gamma distribution with the given shape `k` and scale `theta`.
+    ///
+    /// ## Errors
+    /// Returns `GammaError` if either parameter is not finite and positive.
+    pub fn new(shape: F, scale: F) -> Result<Self, GammaError> {
+        if !positive_finite(shape.to_f64()) {
+            return Err(GammaError::BadShape);
+        }
+        if !positive_finite(scale.to_f64()) {
+            return Err(GammaError::BadScale);
+        }
+        Ok(Gamma { shape, scale })
+    }
+
+    /// The shape `k`.
+    pub fn shape(&self) -> F {
+        self.shape
+    }
+
+    /// The scale `theta`.
+    pub fn scale(&self) -> F {
+        self.scale
+    }
+}
+
+impl<F: Float> Distribution<F> for Gamma<F> {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<F> {
+        let x = standard_gamma(rng, self.shape.to_f64())?;
+        Ok(F::from_f64(x * self.scale.to_f64()))
+    }
+}
+
+/// Marsaglia & Tsang for `shape >= 1`, returns a `Gamma(shape, 1)` sample.
+fn marsaglia_tsang<R: RngApi + ?Sized>(rng: &mut R, shape: f64) -> RngResult<f64> {
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x = standard_normal(rng)?;
+        let v = 1.0 + c * x;
+        if v <= 0.0 {
+            continue;
+        }
+        let v = v * v * v;
+        let u = rng.random_unit_f64_open_closed()?;
+        let x2 = x * x;
+        if u < 1.0 - 0.0331 * x2 * x2 || u.ln() < 0.5 * x2 + d * (1.0 - v + v.ln()) {
+            return Ok(d * v);
+        }
+    }
+}
+
+/// Draw from `Gamma(shape, 1)`.
+pub(crate) fn standard_gamma<R: RngApi + ?Sized>(rng: &mut R, shape: f64) -> RngResult<f64> {
+    if shape >= 1.0 {
+        marsaglia_tsang(rng, shape)
+    } else {
+        let boosted = marsaglia_tsang(rng, shape + 1.0)?;
+        Ok(boosted * rng.random_unit_f64_open_closed()?.powf(1.0 / shape))
+    }
+}
+
+/// The logarithm of a `Gamma(shape, 1)` sample.
+///
+/// For small shapes the sample itself underflows to 0 far more often than its logarithm,
+/// which `Beta` relies on.
+pub(crate) fn ln_standard_gamma<R: RngApi + ?Sized>(rng: &mut R, shape: f64) -> RngResult<f64> {
+    if shape >= 1.0 {
+        Ok(marsaglia_tsang(rng, shape)?.ln())
+    } else {
+        let boosted = marsaglia_tsang(rng, shape + 1.0)?.ln();
+        Ok(boosted + rng.random_unit_f64_open_closed()?.ln() / shape)
+    }
+}
+
+/// Why a [`ChiSquared`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChiSquaredError {
+    /// The degrees of freedom are not a finite, positive number.
+    BadDegreesOfFreedom,
+}
+
+impl Display for ChiSquaredError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ChiSquaredError::BadDegreesOfFreedom => {
+                write!(f, "The degrees of freedom must be finite and positive!")
+            }
+        }
+    }
+}
+
+impl Error for ChiSquaredError {}
+
+impl From<ChiSquaredError> for RngError {
+    fn from(e: ChiSquaredError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// The chi-squared distribution with `k` degrees of freedom, `Gamma(k / 2, 2)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChiSquared<F: Float> {
+    k: F,
+}
+
+impl<F: Float> ChiSquared<F> {
+    /// Create a chi-squared distribution with `k` degrees of freedom, `k` need not be an integer.
+    ///
+    /// ## Errors
+    /// Returns `ChiSquaredError` if `k` is not finite and positive.
+    pub fn new(k: F) -> Result<Self, ChiSquaredError> {
+        if !positive_finite(k.to_f64()) {
+            return Err(ChiSquaredError::BadDegreesOfFreedom);
+        }
+        Ok(ChiSquared { k })
+    }
+
+    /// The degrees of freedom.
+    pub fn degrees_of_freedom(&self) -> F {
+        self.k
+    }
+}
+
+impl<F: Float> Distribution<F> for ChiSquared<F> {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<F> {
+        let x = standard_gamma(rng, 0.5 * self.k.to_f64())?;
+        Ok(F::from_f64(2.0 * x))
+    }
+}
+
+/// Why a [`Beta`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BetaError {
+    /// Alpha is not a finite, positive number.
+    BadAlpha,
+    /// Beta is not a finite, positive number.
+    BadBeta,
+}
+
+impl Display for BetaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            BetaError::BadAlpha => write!(f, "Alpha must be finite and positive!"),
+            BetaError::BadBeta => write!(f, "Beta must be finite and positive!"),
+        }
+    }
+}
+
+impl Error for BetaError {}
+
+impl From<BetaError> for RngError {
+    fn from(e: BetaError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// The beta distribution `Beta(alpha, beta)` on `[0, 1]`, with mean `alpha / (alpha + beta)`.
+///
+/// Sampled as `X / (X + Y)` for `X ~ Gamma(alpha, 1)` and `Y ~ Gamma(beta, 1)`, computed from
+/// their logarithms so that tiny parameters do not produce `0 / 0`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Beta<F: Float> {
+    alpha: F,
+    beta: F,
+}
+
+impl<F: Float> Beta<F> {
+    /// Create a beta distribution with shape parameters `alpha` and `beta`.
+    ///
+    /// ## Errors
+    /// Returns `BetaError` if either parameter is not finite and positive.
+    pub fn new(alpha: F, beta: F) -> Result<Self, BetaError> {
+        if !positive_finite(alpha.to_f64()) {
+            return Err(BetaError::BadAlpha);
+        }
+        if !positive_finite(beta.to_f64()) {
+            return Err(BetaError::BadBeta);
+        }
+        Ok(Beta { alpha, beta })
+    }
+
+    /// The first shape parameter.
+    pub fn alpha(&self) -> F {
+        self.alpha
+    }
+
+    /// The second shape parameter.
+    pub fn beta(&self) -> F {
+        self.beta
+    }
+}
+
+impl<F: Float> Distribution<F> for Beta<F> {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<F> {
+        let ln_x = ln_standard_gamma(rng, self.alpha.to_f64())?;
+        let ln_y = ln_standard_gamma(rng, self.beta.to_f64())?;
+        // X / (X + Y) = 1 / (1 + Y / X)
+        Ok(F::from_f64(1.0 / (1.0 + (ln_y - ln_x).exp())))
+    }
+}
@@ -0,0 +1,250 @@
+//! The geometric and hypergeometric distributions.
+
+use super::Distribution;
+use super::math::ln_factorial;
+use crate::RngApiExt;
+use crate::uniform;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Why a [`Geometric`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GeometricError {
+    /// The probability is NaN or outside `(0, 1]`.
+    BadProbability,
+}
+
+impl Display for GeometricError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            GeometricError::BadProbability => write!(f, "The probability must be in (0, 1]!"),
+        }
+    }
+}
+
+impl Error for GeometricError {}
+
+impl From<GeometricError> for RngError {
+    fn from(e: GeometricError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// The geometric distribution `Geometric(p)`, the number of failures before the first success
+/// in trials that each succeed with probability `p`.
+///
+/// Sampled by inversion, `floor(ln(U) / ln(1 - p))`. Results too large for a `u64`, only
+/// possible for `p` below about `1e-18`, saturate at `u64::MAX`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Geometric {
+    p: f64,
+    /// `ln(1 - p)`.
+    ln_q: f64,
+}
+
+impl Geometric {
+    /// Create a geometric distribution with success probability `p`.
+    ///
+    /// ## Errors
+    /// Returns `GeometricError` if `p` is NaN or outside `(0, 1]`.
+    pub fn new(p: f64) -> Result<Self, GeometricError> {
+        if !(p > 0.0 && p <= 1.0) {
+            return Err(GeometricError::BadProbability);
+        }
+        Ok(Geometric {
+            p,
+            ln_q: (-p).ln_1p(),
+        })
+    }
+
+    /// The success probability of every trial.
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+}
+
+impl Distribution<u64> for Geometric {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<u64> {
+        if self.p == 1.0 {
+            return Ok(0);
+        }
+        let u = rng.random_unit_f64_open_closed()?;
+        // `as` saturates
+        Ok((u.ln() / self.ln_q).floor() as u64)
+    }
+}
+
+/// Why a [`Hypergeometric`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HypergeometricError {
+    /// There are more successes than items in the population.
+    TooManySuccesses,
+    /// There are more draws than items in the population.
+    TooManyDraws,
+}
+
+impl Display for HypergeometricError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            HypergeometricError::TooManySuccesses => {
+                write!(f, "There are more successes than items in the population!")
+            }
+            HypergeometricError::TooManyDraws => {
+                write!(f, "There are more draws than items in the population!")
+            }
+        }
+    }
+}
+
+impl Error for HypergeometricError {}
+
+impl From<HypergeometricError> for RngError {
+    fn from(e: HypergeometricError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// Up to this many draws, simulating them one by one beats HRUA.
+const SIMULATION_THRESHOLD: u64 = 10;
+/// `2 * sqrt(2 / e)`, the width factor of HRUA's hat.
+const HRUA_D1: f64 = 1.715_527_769_921_413_5;
+/// `3 - 2 * sqrt(3 / e)`, the offset of HRUA's hat.
+const HRUA_D2: f64 = 0.898_916_162_058_898_8;
+
+/// The hypergeometric distribution, the number of successes among `draws` items drawn without
+/// replacement from a population of `total` items, `successes` of which count as a success.
+///
+/// Few draws are simulated one by one; otherwise the ratio-of-uniforms algorithm HRUA of
+/// Stadlober, "Sampling from Poisson, binomial and hypergeometric distributions", 1989, is
+/// used, whose cost does not grow with the population.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hypergeometric {
+    total: u64,
+    successes: u64,
+    draws: u64,
+}
+
+impl Hypergeometric {
+    /// Create a hypergeometric distribution.
+    ///
+    /// ## Errors
+    /// Returns `HypergeometricError` if `successes` or `draws` exceed `total`.
+    pub fn new(total: u64, successes: u64, draws: u64) -> Result<Self, HypergeometricError> {
+        if successes > total {
+            return Err(HypergeometricError::TooManySuccesses);
+        }
+        if draws > total {
+            return Err(HypergeometricError::TooManyDraws);
+        }
+        Ok(Hypergeometric {
+            total,
+            successes,
+            draws,
+        })
+    }
+
+    /// The population size.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// The number of successes in the population.
+    pub fn successes(&self) -> u64 {
+        self.successes
+    }
+
+    /// The number of items drawn.
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+}
+
+impl Distribution<u64> for Hypergeometric {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<u64> {
+        // Leaving `total - draws` items behind is the mirror image of drawing them
+        let complement = self.draws > self.total - self.draws;
+        let draws = if complement {
+            self.total - self.draws
+        } else {
+            self.draws
+        };
+        let drawn = if draws <= SIMULATION_THRESHOLD {
+            simulate(rng, self.total, self.successes, draws)?
+        } else {
+            hrua(rng, self.total, self.successes, draws)?
+        };
+        Ok(if complement {
+            self.successes - drawn
+        } else {
+            drawn
+        })
+    }
+}
+
+/// Draw one item at a time.
+fn simulate<R: RngApi + ?Sized>(
+    rng: &mut R,
+    total: u64,
+    successes: u64,
+    draws: u64,
+) -> RngResult<u64> {
+    let mut remaining = total;
+    let mut remaining_successes = successes;
+    for _ in 0..draws {
+        if uniform::u64_below(rng, remaining)? < remaining_successes {
+            remaining_successes -= 1;
+        }
+        remaining -= 1;
+    }
+    Ok(successes - remaining_successes)
+}
+
+/// HRUA with `draws <= total / 2`.
+fn hrua<R: RngApi + ?Sized>(rng: &mut R, total: u64, successes: u64, draws: u64) -> RngResult<u64> {
+    // Count the rarer kind of item, and flip back at the end
+    let flipped = successes > total - successes;
+    let rare = successes.min(total - successes) as f64;
+    let common = successes.max(total - successes) as f64;
+    let totalf = total as f64;
+    let drawsf = draws as f64;
+
+    let p = rare / totalf;
+    let q = common / totalf;
+    let a = drawsf * p + 0.5;
+    let var = (totalf - drawsf) * drawsf * p * q / (totalf - 1.0);
+    let c = (var + 0.5).sqrt();
+    let h = HRUA_D1 * c + HRUA_D2;
+    let mode = ((drawsf + 1.0) * (rare + 1.0) / (totalf + 2.0)).floor();
+    let ln_f = |k: f64| {
+        ln_factorial(k)
+            + ln_factorial(rare - k)
+            + ln_factorial(drawsf - k)
+            + ln_factorial(common - drawsf + k)
+    };
+    let g = ln_f(mode);
+    // Past 16 standard deviations the density is negligible
+    let bound = (drawsf.min(rare) + 1.0).min((a + 16.0 * c).floor());
+
+    let k = loop {
+        let u = rng.random_unit_f64_open_closed()?;
+        let v = rng.random_unit_f64()?;
+        let x = a + h * (v - 0.5) / u;
+        if x < 0.0 || x >= bound {
+            continue;
+        }
+        let k = x.floor();
+        let t = g - ln_f(k);
+        // Fast acceptance and rejection with bounds on the logarithm, then the exact test
+        if u * (4.0 - u) - 3.0 <= t {
+            break k;
+        }
+        if u * (u - t) >= 1.0 {
+            continue;
+        }
+        if 2.0 * u.ln() <= t {
+            break k;
+        }
+    } as u64;
+    Ok(if flipped { draws - k } else { k })
+}
@@ -0,0 +1,102 @@
+//! Non-uniform distributions, sampled with any [`RngApi`] generator such as [`Tyche`](crate::Tyche).
+//!
+//! Every distribution validates its parameters on construction and reports invalid ones
+//! through its own error type, which converts into `RngError`.
+//!
+//! ## Example
+//! ```
+//! use tyche::Tyche;
+//! use tyche::distributions::{Distribution, Normal};
+//!
+//! let jitter = Normal::new(0.0, 2.5).unwrap();
+//! let mut tyche = Tyche::new().unwrap();
+//! let delay_ms: f64 = 100.0 + jitter.sample(&mut tyche).unwrap();
+//! assert!(delay_ms.is_finite());
+//! ```
+
+#[cfg(test)]
+mod tests;
+
+mod bernoulli;
+mod binomial;
+mod cauchy;
+mod dirichlet;
+mod exponential;
+mod gamma;
+mod geometric;
+mod math;
+mod normal;
+mod pareto;
+mod poisson;
+mod unit;
+mod ziggurat;
+mod zipf;
+
+pub use bernoulli::{Bernoulli, BernoulliError};
+pub use binomial::{Binomial, BinomialError};
+pub use cauchy::{Cauchy, CauchyError};
+pub use dirichlet::{Dirichlet, DirichletError, Simplex};
+pub use exponential::{Exp, ExpError};
+pub use gamma::{Beta, BetaError, ChiSquared, ChiSquaredError, Gamma, GammaError};
+pub use geometric::{Geometric, GeometricError, Hypergeometric, HypergeometricError};
+pub use normal::{LogNormal, Normal, NormalError, StandardNormal};
+pub use pareto::{Pareto, ScaleShapeError, Weibull};
+pub use poisson::{Poisson, PoissonError};
+pub use unit::{
+    DimensionError, UnitBall, UnitCircle, UnitDisc, UnitHyperball, UnitHypersphere, UnitSphere,
+};
+pub use zipf::{Zipf, ZipfError};
+
+use athena::rng_api::{RngApi, RngResult};
+use std::fmt::{Debug, Display};
+
+/// A probability distribution producing values of type `T`.
+pub trait Distribution<T> {
+    /// Draw one value from the distribution.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<T>;
+}
+
+impl<T, D: Distribution<T> + ?Sized> Distribution<T> for &D {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<T> {
+        (**self).sample(rng)
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// The float types distributions are generic over, `f32` and `f64`.
+///
+/// Sampling happens in `f64` and is rounded to the target type at the end.
+pub trait Float: Copy + PartialOrd + Debug + Display + private::Sealed {
+    /// Convert to `f64`, exactly.
+    fn to_f64(self) -> f64;
+    /// Round an `f64` to this type.
+    fn from_f64(x: f64) -> Self;
+}
+
+impl Float for f32 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+}
+
+impl Float for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+}
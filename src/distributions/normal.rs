@@ -0,0 +1,133 @@
+//! The normal (Gaussian) distribution.
+
+use super::ziggurat::standard_normal;
+use super::{Distribution, Float};
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The standard normal distribution, mean 0 and standard deviation 1.
+///
+/// The fast path: no parameters to validate and no scaling.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StandardNormal;
+
+impl Distribution<f64> for StandardNormal {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<f64> {
+        standard_normal(rng)
+    }
+}
+
+impl Distribution<f32> for StandardNormal {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<f32> {
+        Ok(standard_normal(rng)? as f32)
+    }
+}
+
+/// Why a [`Normal`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalError {
+    /// The mean is NaN or infinite.
+    MeanNotFinite,
+    /// The standard deviation is negative, NaN or infinite.
+    BadStdDev,
+}
+
+impl Display for NormalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            NormalError::MeanNotFinite => write!(f, "The mean must be finite!"),
+            NormalError::BadStdDev => {
+                write!(f, "The standard deviation must be finite and non-negative!")
+            }
+        }
+    }
+}
+
+impl Error for NormalError {}
+
+impl From<NormalError> for RngError {
+    fn from(e: NormalError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// The normal distribution `N(mean, std_dev²)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Normal<F: Float> {
+    mean: F,
+    std_dev: F,
+}
+
+impl<F: Float> Normal<F> {
+    /// Create a normal distribution with the given mean and standard deviation.
+    ///
+    /// A standard deviation of 0 always yields the mean.
+    ///
+    /// ## Errors
+    /// Returns `NormalError` if `mean` is not finite, or `std_dev` is negative or not finite.
+    pub fn new(mean: F, std_dev: F) -> Result<Self, NormalError> {
+        if !mean.to_f64().is_finite() {
+            return Err(NormalError::MeanNotFinite);
+        }
+        let sd = std_dev.to_f64();
+        if !(sd.is_finite() && sd >= 0.0) {
+            return Err(NormalError::BadStdDev);
+        }
+        Ok(Normal { mean, std_dev })
+    }
+
+    /// The mean.
+    pub fn mean(&self) -> F {
+        self.mean
+    }
+
+    /// The standard deviation.
+    pub fn std_dev(&self) -> F {
+        self.std_dev
+    }
+}
+
+impl<F: Float> Distribution<F> for Normal<F> {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<F> {
+        let z = standard_normal(rng)?;
+        Ok(F::from_f64(self.mean.to_f64() + self.std_dev.to_f64() * z))
+    }
+}
+
+/// The log-normal distribution, `exp(X)` for `X ~ N(mu, sigma²)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LogNormal<F: Float> {
+    normal: Normal<F>,
+}
+
+impl<F: Float> LogNormal<F> {
+    /// Create a log-normal distribution whose logarithm has mean `mu` and standard deviation
+    /// `sigma`.
+    ///
+    /// ## Errors
+    /// Returns `NormalError` if `mu` is not finite, or `sigma` is negative or not finite.
+    pub fn new(mu: F, sigma: F) -> Result<Self, NormalError> {
+        Ok(LogNormal {
+            normal: Normal::new(mu, sigma)?,
+        })
+    }
+
+    /// The mean of the logarithm.
+    pub fn mu(&self) -> F {
+        self.normal.mean
+    }
+
+    /// The standard deviation of the logarithm.
+    pub fn sigma(&self) -> F {
+        self.normal.std_dev
+    }
+}
+
+impl<F: Float> Distribution<F> for LogNormal<F> {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<F> {
+        let z = standard_normal(rng)?;
+        let x = self.normal.mean.to_f64() + self.normal.std_dev.to_f64() * z;
+        Ok(F::from_f64(x.exp()))
+    }
+}
@@ -0,0 +1,128 @@
+//! The Pareto and Weibull distributions.
+
+use super::{Distribution, Float};
+use crate::RngApiExt;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+fn positive_finite(x: f64) -> bool {
+    x.is_finite() && x > 0.0
+}
+
+/// Why a [`Pareto`] or [`Weibull`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScaleShapeError {
+    /// The scale is not a finite, positive number.
+    BadScale,
+    /// The shape is not a finite, positive number.
+    BadShape,
+}
+
+impl Display for ScaleShapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ScaleShapeError::BadScale => write!(f, "The scale must be finite and positive!"),
+            ScaleShapeError::BadShape => write!(f, "The shape must be finite and positive!"),
+        }
+    }
+}
+
+impl Error for ScaleShapeError {}
+
+impl From<ScaleShapeError> for RngError {
+    fn from(e: ScaleShapeError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+fn check(scale: f64, shape: f64) -> Result<(), ScaleShapeError> {
+    if !positive_finite(scale) {
+        return Err(ScaleShapeError::BadScale);
+    }
+    if !positive_finite(shape) {
+        return Err(ScaleShapeError::BadShape);
+    }
+    Ok(())
+}
+
+/// The Pareto distribution with minimum `scale` and tail index `shape`,
+/// `P(X > x) = (scale / x)^shape` for `x >= scale`.
+///
+/// The mean is infinite for `shape <= 1` and the variance for `shape <= 2`. Sampled by
+/// inversion, `scale * U^(-1 / shape)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pareto<F: Float> {
+    scale: F,
+    shape: F,
+}
+
+impl<F: Float> Pareto<F> {
+    /// Create a Pareto distribution.
+    ///
+    /// ## Errors
+    /// Returns `ScaleShapeError` if either parameter is not finite and positive.
+    pub fn new(scale: F, shape: F) -> Result<Self, ScaleShapeError> {
+        check(scale.to_f64(), shape.to_f64())?;
+        Ok(Pareto { scale, shape })
+    }
+
+    /// The smallest possible value.
+    pub fn scale(&self) -> F {
+        self.scale
+    }
+
+    /// The tail index.
+    pub fn shape(&self) -> F {
+        self.shape
+    }
+}
+
+impl<F: Float> Distribution<F> for Pareto<F> {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<F> {
+        let u = rng.random_unit_f64_open_closed()?;
+        Ok(F::from_f64(
+            self.scale.to_f64() * u.powf(-1.0 / self.shape.to_f64()),
+        ))
+    }
+}
+
+/// The Weibull distribution, `P(X > x) = exp(-(x / scale)^shape)` for `x >= 0`.
+///
+/// Shapes below 1 give a heavy tail, 1 is the exponential distribution. Sampled by
+/// inversion, `scale * (-ln(U))^(1 / shape)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Weibull<F: Float> {
+    scale: F,
+    shape: F,
+}
+
+impl<F: Float> Weibull<F> {
+    /// Create a Weibull distribution.
+    ///
+    /// ## Errors
+    /// Returns `ScaleShapeError` if either parameter is not finite and positive.
+    pub fn new(scale: F, shape: F) -> Result<Self, ScaleShapeError> {
+        check(scale.to_f64(), shape.to_f64())?;
+        Ok(Weibull { scale, shape })
+    }
+
+    /// The scale.
+    pub fn scale(&self) -> F {
+        self.scale
+    }
+
+    /// The shape.
+    pub fn shape(&self) -> F {
+        self.shape
+    }
+}
+
+impl<F: Float> Distribution<F> for Weibull<F> {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<F> {
+        let e = -rng.random_unit_f64_open_closed()?.ln();
+        Ok(F::from_f64(
+            self.scale.to_f64() * e.powf(1.0 / self.shape.to_f64()),
+        ))
+    }
+}
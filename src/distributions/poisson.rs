@@ -0,0 +1,125 @@
+//! The Poisson distribution.
+
+use super::Distribution;
+use super::math::ln_factorial;
+use crate::RngApiExt;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Why a [`Poisson`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoissonError {
+    /// The rate is not positive, is NaN, or exceeds [`Poisson::MAX_LAMBDA`].
+    BadLambda,
+}
+
+impl Display for PoissonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            PoissonError::BadLambda => write!(
+                f,
+                "The rate must be positive and at most {}!",
+                Poisson::MAX_LAMBDA
+            ),
+        }
+    }
+}
+
+impl Error for PoissonError {}
+
+impl From<PoissonError> for RngError {
+    fn from(e: PoissonError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// Below this rate, inversion is faster than PTRS.
+const INVERSION_THRESHOLD: f64 = 10.0;
+
+/// The Poisson distribution `Poisson(lambda)`, the number of events in a unit of time when
+/// they occur `lambda` times per unit on average.
+///
+/// Small rates are sampled by inversion, large ones with the PTRS algorithm of Hörmann,
+/// "The transformed rejection method for generating Poisson random variables", 1993, whose
+/// cost does not grow with `lambda`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Poisson {
+    lambda: f64,
+}
+
+impl Poisson {
+    /// The largest supported rate, `2^53`; every count up to it is exact in an `f64`.
+    pub const MAX_LAMBDA: f64 = 9_007_199_254_740_992.0;
+
+    /// Create a Poisson distribution with rate `lambda`.
+    ///
+    /// ## Errors
+    /// Returns `PoissonError` if `lambda` is not positive, is NaN or exceeds [`Self::MAX_LAMBDA`].
+    pub fn new(lambda: f64) -> Result<Self, PoissonError> {
+        if !(lambda > 0.0 && lambda <= Self::MAX_LAMBDA) {
+            return Err(PoissonError::BadLambda);
+        }
+        Ok(Poisson { lambda })
+    }
+
+    /// The rate.
+    pub fn lambda(&self) -> f64 {
+        self.lambda
+    }
+}
+
+impl Distribution<u64> for Poisson {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<u64> {
+        if self.lambda < INVERSION_THRESHOLD {
+            inversion(rng, self.lambda)
+        } else {
+            ptrs(rng, self.lambda)
+        }
+    }
+}
+
+/// Walk the cumulative distribution from 0 upwards.
+fn inversion<R: RngApi + ?Sized>(rng: &mut R, lambda: f64) -> RngResult<u64> {
+    'restart: loop {
+        let mut u = rng.random_unit_f64()?;
+        let mut p = (-lambda).exp();
+        let mut x = 0;
+        while u > p {
+            u -= p;
+            x += 1;
+            p *= lambda / x as f64;
+            // Only reachable through rounding drift, the density underflows long before
+            if p == 0.0 {
+                continue 'restart;
+            }
+        }
+        return Ok(x);
+    }
+}
+
+/// PTRS: a transformed uniform sample with a fast acceptance region and an exact test.
+fn ptrs<R: RngApi + ?Sized>(rng: &mut R, lambda: f64) -> RngResult<u64> {
+    let ln_lambda = lambda.ln();
+    let b = 0.931 + 2.53 * lambda.sqrt();
+    let a = -0.059 + 0.02483 * b;
+    let ln_inv_alpha = (1.1239 + 1.1328 / (b - 3.4)).ln();
+    let v_r = 0.9277 - 3.6224 / (b - 2.0);
+    loop {
+        let u = rng.random_unit_f64()? - 0.5;
+        let v = rng.random_unit_f64_open_closed()?;
+        let us = 0.5 - u.abs();
+        let k = ((2.0 * a / us + b) * u + lambda + 0.43).floor();
+        if us >= 0.07 && v <= v_r {
+            return Ok(k as u64);
+        }
+        if k < 0.0 || (us < 0.013 && v > us) {
+            continue;
+        }
+        if v.ln() + ln_inv_alpha - (a / (us * us) + b).ln()
+            <= -lambda + k * ln_lambda - ln_factorial(k)
+        {
+            return Ok(k as u64);
+        }
+    }
+}
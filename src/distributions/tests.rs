@@ -0,0 +1,822 @@
+use super::*;
+use crate::{RngApiExt, Tyche, WeightedIndex};
+
+const SAMPLES: usize = 200_000;
+
+/// Sample mean and variance.
+fn moments(samples: &[f64]) -> (f64, f64) {
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let var = samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (n - 1.0);
+    (mean, var)
+}
+
+/// Kolmogorov–Smirnov statistic of `samples` against `cdf`.
+fn ks_statistic(samples: &mut [f64], cdf: impl Fn(f64) -> f64) -> f64 {
+    samples.sort_by(f64::total_cmp);
+    let n = samples.len() as f64;
+    samples
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let f = cdf(x);
+            (f - i as f64 / n).abs().max(((i + 1) as f64 / n - f).abs())
+        })
+        .fold(0.0, f64::max)
+}
+
+/// KS critical value for p = 1e-6, `sqrt(-ln(p / 2) / 2) / sqrt(n)`.
+fn ks_critical(n: usize) -> f64 {
+    2.69 / (n as f64).sqrt()
+}
+
+/// Complementary error function, fractional error below 1.2e-7 (Numerical Recipes `erfcc`).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let r = t * poly.exp();
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+fn normal_cdf(x: f64, mean: f64, std_dev: f64) -> f64 {
+    0.5 * erfc(-(x - mean) / (std_dev * std::f64::consts::SQRT_2))
+}
+
+#[test]
+fn test_standard_normal_moments() {
+    let mut rng = Tyche::new().unwrap();
+    let samples = (0..SAMPLES)
+        .map(|_| rng.sample(&StandardNormal))
+        .collect::<Result<Vec<f64>, _>>()
+        .unwrap();
+    let (mean, var) = moments(&samples);
+    // Standard errors are 1/sqrt(n) and sqrt(2/n), allow ~6 of them
+    assert!(mean.abs() < 0.014, "mean {mean}");
+    assert!((var - 1.0).abs() < 0.02, "variance {var}");
+    let kurtosis = samples.iter().map(|x| x.powi(4)).sum::<f64>() / SAMPLES as f64;
+    assert!((kurtosis - 3.0).abs() < 0.1, "fourth moment {kurtosis}");
+}
+
+#[test]
+fn test_standard_normal_matches_cdf() {
+    let mut rng = Tyche::new().unwrap();
+    let mut samples = (0..SAMPLES)
+        .map(|_| rng.sample(&StandardNormal))
+        .collect::<Result<Vec<f64>, _>>()
+        .unwrap();
+    let d = ks_statistic(&mut samples, |x| normal_cdf(x, 0.0, 1.0));
+    assert!(d < ks_critical(SAMPLES), "KS statistic {d}");
+}
+
+#[test]
+fn test_standard_normal_tails() {
+    // P(|Z| > 3.6541...) ~= 2.58e-4, the tail algorithm must produce them at that rate
+    let mut rng = Tyche::new().unwrap();
+    let n = 1_000_000;
+    let mut beyond_r = 0;
+    let mut beyond_4 = 0;
+    for _ in 0..n {
+        let z: f64 = rng.sample(&StandardNormal).unwrap();
+        if z.abs() > 3.654_152_885_361_009 {
+            beyond_r += 1;
+        }
+        if z.abs() > 4.0 {
+            beyond_4 += 1;
+        }
+    }
+    let expected_r = n as f64 * 2.0 * normal_cdf(-3.654_152_885_361_009, 0.0, 1.0);
+    let expected_4 = n as f64 * 2.0 * normal_cdf(-4.0, 0.0, 1.0);
+    // Poisson counts, allow 6 standard deviations
+    assert!(
+        (beyond_r as f64 - expected_r).abs() < 6.0 * expected_r.sqrt(),
+        "{beyond_r} vs {expected_r}"
+    );
+    assert!(
+        (beyond_4 as f64 - expected_4).abs() < 6.0 * expected_4.sqrt() + 1.0,
+        "{beyond_4} vs {expected_4}"
+    );
+}
+
+#[test]
+fn test_normal_f64_matches_cdf() {
+    let normal = Normal::new(-3.0, 2.5).unwrap();
+    let mut rng = Tyche::new().unwrap();
+    let mut samples = (0..SAMPLES)
+        .map(|_| normal.sample(&mut rng))
+        .collect::<Result<Vec<f64>, _>>()
+        .unwrap();
+    let (mean, var) = moments(&samples);
+    assert!((mean + 3.0).abs() < 0.035, "mean {mean}");
+    assert!((var - 6.25).abs() < 0.13, "variance {var}");
+    let d = ks_statistic(&mut samples, |x| normal_cdf(x, -3.0, 2.5));
+    assert!(d < ks_critical(SAMPLES), "KS statistic {d}");
+}
+
+#[test]
+fn test_normal_f32_matches_cdf() {
+    let normal = Normal::new(10.0f32, 0.5).unwrap();
+    let mut rng = Tyche::new().unwrap();
+    let mut samples = (0..SAMPLES)
+        .map(|_| normal.sample(&mut rng).map(f64::from))
+        .collect::<Result<Vec<f64>, _>>()
+        .unwrap();
+    let d = ks_statistic(&mut samples, |x| normal_cdf(x, 10.0, 0.5));
+    assert!(d < ks_critical(SAMPLES), "KS statistic {d}");
+    let z: f32 = StandardNormal.sample(&mut rng).unwrap();
+    assert!(z.is_finite());
+}
+
+#[test]
+fn test_normal_zero_std_dev_is_constant() {
+    let normal = Normal::new(4.2, 0.0).unwrap();
+    let mut rng = Tyche::new().unwrap();
+    for _ in 0..100 {
+        assert_eq!(normal.sample(&mut rng).unwrap(), 4.2);
+    }
+}
+
+#[test]
+fn test_normal_rejects_bad_parameters() {
+    assert_eq!(Normal::new(f64::NAN, 1.0), Err(NormalError::MeanNotFinite));
+    assert_eq!(
+        Normal::new(f64::INFINITY, 1.0),
+        Err(NormalError::MeanNotFinite)
+    );
+    assert_eq!(Normal::new(0.0, -1.0), Err(NormalError::BadStdDev));
+    assert_eq!(Normal::new(0.0f32, f32::NAN), Err(NormalError::BadStdDev));
+    assert_eq!(Normal::new(0.0, f64::INFINITY), Err(NormalError::BadStdDev));
+    let normal = Normal::new(1.5, 0.25).unwrap();
+    assert_eq!((normal.mean(), normal.std_dev()), (1.5, 0.25));
+}
+
+#[test]
+fn test_weighted_index_is_a_distribution() {
+    fn draw<D: Distribution<usize>>(d: &D, rng: &mut Tyche) -> usize {
+        d.sample(rng).unwrap()
+    }
+    let table = WeightedIndex::new([0u32, 1, 0]).unwrap();
+    let mut rng = Tyche::new().unwrap();
+    for _ in 0..100 {
+        assert_eq!(draw(&table, &mut rng), 1);
+    }
+}
+
+/// Check the sample mean and variance against the exact values, allowing 6 standard errors.
+///
+/// The standard error of the variance depends on the excess kurtosis of the distribution.
+fn assert_moments(samples: &[f64], mean: f64, var: f64, excess_kurtosis: f64) {
+    let n = samples.len() as f64;
+    let (sample_mean, sample_var) = moments(samples);
+    let mean_tolerance = 6.0 * (var / n).sqrt();
+    let var_tolerance = 6.0 * var * ((2.0 + excess_kurtosis) / n).sqrt();
+    assert!(
+        (sample_mean - mean).abs() < mean_tolerance,
+        "mean {sample_mean}, expected {mean}"
+    );
+    assert!(
+        (sample_var - var).abs() < var_tolerance,
+        "variance {sample_var}, expected {var}"
+    );
+}
+
+fn draw<D: Distribution<f64>>(distribution: &D) -> Vec<f64> {
+    let mut rng = Tyche::new().unwrap();
+    (0..SAMPLES)
+        .map(|_| distribution.sample(&mut rng))
+        .collect::<Result<Vec<f64>, _>>()
+        .unwrap()
+}
+
+#[test]
+fn test_exp_moments_and_cdf() {
+    for lambda in [0.1, 1.0, 7.5] {
+        let mut samples = draw(&Exp::new(lambda).unwrap());
+        assert!(samples.iter().all(|&x| x >= 0.0));
+        assert_moments(&samples, 1.0 / lambda, 1.0 / (lambda * lambda), 6.0);
+        let d = ks_statistic(&mut samples, |x| 1.0 - (-lambda * x).exp());
+        assert!(d < ks_critical(SAMPLES), "KS statistic {d}");
+    }
+}
+
+#[test]
+fn test_gamma_moments() {
+    for (shape, scale) in [(0.1, 1.0), (0.5, 3.0), (1.0, 1.0), (2.5, 0.5), (40.0, 2.0)] {
+        let samples = draw(&Gamma::new(shape, scale).unwrap());
+        assert!(samples.iter().all(|&x| x >= 0.0));
+        assert_moments(&samples, shape * scale, shape * scale * scale, 6.0 / shape);
+    }
+}
+
+#[test]
+fn test_gamma_one_is_exponential() {
+    let mut samples = draw(&Gamma::new(1.0, 2.0).unwrap());
+    let d = ks_statistic(&mut samples, |x| 1.0 - (-x / 2.0).exp());
+    assert!(d < ks_critical(SAMPLES), "KS statistic {d}");
+}
+
+#[test]
+fn test_chi_squared_moments() {
+    for k in [0.5, 1.0, 3.0, 30.0] {
+        let samples = draw(&ChiSquared::new(k).unwrap());
+        assert_moments(&samples, k, 2.0 * k, 12.0 / k);
+    }
+    // Two degrees of freedom is Exp(1 / 2)
+    let mut samples = draw(&ChiSquared::new(2.0).unwrap());
+    let d = ks_statistic(&mut samples, |x| 1.0 - (-x / 2.0).exp());
+    assert!(d < ks_critical(SAMPLES), "KS statistic {d}");
+}
+
+#[test]
+fn test_beta_moments() {
+    for (a, b) in [(0.5, 0.5), (2.0, 5.0), (1.0, 1.0), (0.2, 3.0), (30.0, 10.0)] {
+        let samples = draw(&Beta::new(a, b).unwrap());
+        assert!(samples.iter().all(|&x| (0.0..=1.0).contains(&x)));
+        let mean = a / (a + b);
+        let var = a * b / ((a + b) * (a + b) * (a + b + 1.0));
+        let excess = 6.0 * ((a - b) * (a - b) * (a + b + 1.0) - a * b * (a + b + 2.0))
+            / (a * b * (a + b + 2.0) * (a + b + 3.0));
+        assert_moments(&samples, mean, var, excess);
+    }
+    // Beta(1, 1) is uniform
+    let mut samples = draw(&Beta::new(1.0, 1.0).unwrap());
+    let d = ks_statistic(&mut samples, |x| x);
+    assert!(d < ks_critical(SAMPLES), "KS statistic {d}");
+}
+
+#[test]
+fn test_beta_tiny_parameters_stay_finite() {
+    let beta = Beta::new(1e-3, 1e-3).unwrap();
+    let mut rng = Tyche::new().unwrap();
+    for _ in 0..10_000 {
+        let x = beta.sample(&mut rng).unwrap();
+        assert!((0.0..=1.0).contains(&x), "{x}");
+    }
+}
+
+#[test]
+fn test_f32_samplers() {
+    let mut rng = Tyche::new().unwrap();
+    let x: f32 = Exp::new(2.0f32).unwrap().sample(&mut rng).unwrap();
+    let y: f32 = Gamma::new(2.0f32, 1.0).unwrap().sample(&mut rng).unwrap();
+    let z: f32 = Beta::new(2.0f32, 2.0).unwrap().sample(&mut rng).unwrap();
+    let w: f32 = ChiSquared::new(4.0f32).unwrap().sample(&mut rng).unwrap();
+    assert!(x >= 0.0 && y >= 0.0 && (0.0..=1.0).contains(&z) && w >= 0.0);
+}
+
+#[test]
+fn test_gamma_family_rejects_bad_parameters() {
+    assert_eq!(Exp::new(0.0), Err(ExpError::BadLambda));
+    assert_eq!(Exp::new(f64::INFINITY), Err(ExpError::BadLambda));
+    assert_eq!(Gamma::new(-1.0, 1.0), Err(GammaError::BadShape));
+    assert_eq!(Gamma::new(1.0, f64::NAN), Err(GammaError::BadScale));
+    assert_eq!(
+        ChiSquared::new(0.0),
+        Err(ChiSquaredError::BadDegreesOfFreedom)
+    );
+    assert_eq!(Beta::new(0.0, 1.0), Err(BetaError::BadAlpha));
+    assert_eq!(Beta::new(1.0, -2.0), Err(BetaError::BadBeta));
+    let gamma = Gamma::new(2.0, 3.0).unwrap();
+    assert_eq!((gamma.shape(), gamma.scale()), (2.0, 3.0));
+}
+
+/// Chi-square goodness of fit of discrete samples against `pmf`, bins with an expected count
+/// below 5 are merged into their neighbours. Fails at p = 1e-6, using the Wilson–Hilferty
+/// approximation of the critical value.
+fn assert_fits_pmf(samples: &[u64], pmf: impl Fn(u64) -> f64) {
+    let n = samples.len() as f64;
+    let max = *samples.iter().max().unwrap();
+    let mut observed = vec![0usize; max as usize + 1];
+    for &s in samples {
+        observed[s as usize] += 1;
+    }
+    let mut bins = Vec::new();
+    let (mut obs, mut exp, mut mass) = (0.0, 0.0, 0.0);
+    for (k, &count) in observed.iter().enumerate() {
+        let p = pmf(k as u64);
+        obs += count as f64;
+        exp += n * p;
+        mass += p;
+        if exp >= 5.0 {
+            bins.push((obs, exp));
+            (obs, exp) = (0.0, 0.0);
+        }
+    }
+    // Everything past the largest sample goes into the last bin
+    let last = bins.last_mut().unwrap();
+    last.0 += obs;
+    last.1 += exp + n * (1.0 - mass).max(0.0);
+    let stat = bins.iter().map(|(o, e)| (o - e) * (o - e) / e).sum::<f64>();
+    let df = (bins.len() - 1) as f64;
+    let z = 4.753;
+    let critical = df * (1.0 - 2.0 / (9.0 * df) + z * (2.0 / (9.0 * df)).sqrt()).powi(3);
+    assert!(
+        stat < critical,
+        "chi-square {stat} >= {critical} with {df} df"
+    );
+}
+
+fn draw_counts<D: Distribution<u64>>(distribution: &D, amount: usize) -> Vec<u64> {
+    let mut rng = Tyche::new().unwrap();
+    (0..amount)
+        .map(|_| distribution.sample(&mut rng))
+        .collect::<Result<Vec<u64>, _>>()
+        .unwrap()
+}
+
+fn as_f64(samples: &[u64]) -> Vec<f64> {
+    samples.iter().map(|&x| x as f64).collect()
+}
+
+fn ln_choose(n: u64, k: u64) -> f64 {
+    math::ln_factorial(n as f64) - math::ln_factorial(k as f64) - math::ln_factorial((n - k) as f64)
+}
+
+#[test]
+fn test_ln_gamma_matches_reference() {
+    // Reference values from Python's math.lgamma
+    for (x, expected) in [
+        (0.1, 2.252_712_651_734_205_5),
+        (0.5, 0.572_364_942_924_700_4),
+        (1.0, 0.0),
+        (2.0, 0.0),
+        (3.5, 1.200_973_602_347_073_8),
+        (10.0, 12.801_827_480_081_467),
+        (100.0, 359.134_205_369_575_4),
+        (1e6, 12_815_504.569_147_611),
+    ] {
+        let got = math::ln_gamma(x);
+        assert!(
+            (got - expected).abs() <= 1e-13 * expected.abs().max(1.0),
+            "ln_gamma({x}) = {got}"
+        );
+    }
+}
+
+/// A source replaying fixed `u64`s, to drive the Bernoulli comparison chunk by chunk.
+fn replay(words: &[u64]) -> Tyche<crate::ReaderSource<std::io::Cursor<Vec<u8>>>> {
+    let bytes = words
+        .iter()
+        .flat_map(|w| w.to_le_bytes())
+        .collect::<Vec<u8>>();
+    Tyche::from_reader(std::io::Cursor::new(bytes))
+}
+
+#[test]
+fn test_bernoulli_is_exact() {
+    // 0.1 as an f64 is exactly 0x1999999999999A00 / 2^64
+    let chunk = 0x1999_9999_9999_9A00;
+    let tenth = Bernoulli::new(0.1).unwrap();
+    assert!(tenth.sample(&mut replay(&[chunk - 1])).unwrap());
+    assert!(!tenth.sample(&mut replay(&[chunk, 5])).unwrap());
+    assert!(!tenth.sample(&mut replay(&[chunk, 0, 0, 1])).unwrap());
+    assert!(!tenth.sample(&mut replay(&[chunk + 1])).unwrap());
+    // 2^-70 only differs from 0 in the second chunk, its bit 58
+    let tiny = Bernoulli::new(2f64.powi(-70)).unwrap();
+    assert!(tiny.sample(&mut replay(&[0, (1 << 58) - 1])).unwrap());
+    assert!(!tiny.sample(&mut replay(&[0, 1 << 58, 0, 7])).unwrap());
+    assert!(!tiny.sample(&mut replay(&[1])).unwrap());
+    // The smallest subnormal, 2^-1074, is bit 14 of chunk 16
+    let least = Bernoulli::new(f64::from_bits(1)).unwrap();
+    assert!(!least.sample(&mut replay(&[1])).unwrap());
+    let mut words = vec![0; 17];
+    words[16] = (1 << 14) - 1;
+    assert!(least.sample(&mut replay(&words)).unwrap());
+    words[16] = 1 << 14;
+    words.push(1);
+    assert!(!least.sample(&mut replay(&words)).unwrap());
+}
+
+#[test]
+fn test_bernoulli_rate() {
+    let mut rng = Tyche::new().unwrap();
+    for p in [0.0, 0.01, 0.3, 0.5, 0.999, 1.0] {
+        let n = 100_000;
+        let hits = (0..n)
+            .filter(|_| rng.random_bool_with_probability(p).unwrap())
+            .count() as f64;
+        let sd = (n as f64 * p * (1.0 - p)).sqrt();
+        assert!(
+            (hits - n as f64 * p).abs() <= 6.0 * sd,
+            "p = {p}: {hits} hits"
+        );
+    }
+    assert!(rng.random_bool_with_probability(1.5).is_err());
+    assert!(rng.random_bool_with_probability(f64::NAN).is_err());
+    assert_eq!(Bernoulli::new(-0.1), Err(BernoulliError::BadProbability));
+}
+
+#[test]
+fn test_binomial_fits_pmf() {
+    for (n, p) in [(20, 0.3), (100, 0.4), (1000, 0.07), (500, 0.9), (5, 0.5)] {
+        let samples = draw_counts(&Binomial::new(n, p).unwrap(), SAMPLES);
+        assert!(samples.iter().all(|&k| k <= n));
+        let q = 1.0 - p;
+        let npq = n as f64 * p * q;
+        assert_moments(
+            &as_f64(&samples),
+            n as f64 * p,
+            npq,
+            (1.0 - 6.0 * p * q) / npq,
+        );
+        assert_fits_pmf(&samples, |k| {
+            if k > n {
+                0.0
+            } else {
+                (ln_choose(n, k) + k as f64 * p.ln() + (n - k) as f64 * q.ln()).exp()
+            }
+        });
+    }
+}
+
+#[test]
+fn test_binomial_large_parameters() {
+    let (n, p) = (1_000_000_000_000, 0.25);
+    let samples = draw_counts(&Binomial::new(n, p).unwrap(), 50_000);
+    let npq = n as f64 * p * (1.0 - p);
+    assert_moments(&as_f64(&samples), n as f64 * p, npq, 0.0);
+    let mut rng = Tyche::new().unwrap();
+    assert_eq!(Binomial::new(10, 0.0).unwrap().sample(&mut rng).unwrap(), 0);
+    assert_eq!(
+        Binomial::new(10, 1.0).unwrap().sample(&mut rng).unwrap(),
+        10
+    );
+    assert_eq!(Binomial::new(0, 0.5).unwrap().sample(&mut rng).unwrap(), 0);
+    assert_eq!(Binomial::new(10, 1.1), Err(BinomialError::BadProbability));
+    assert_eq!(
+        Binomial::new(u64::MAX, 0.5),
+        Err(BinomialError::TooManyTrials)
+    );
+}
+
+#[test]
+fn test_poisson_fits_pmf() {
+    for lambda in [0.5, 3.0, 9.9, 10.0, 50.0, 1234.5] {
+        let samples = draw_counts(&Poisson::new(lambda).unwrap(), SAMPLES);
+        assert_moments(&as_f64(&samples), lambda, lambda, 1.0 / lambda);
+        assert_fits_pmf(&samples, |k| {
+            (k as f64 * lambda.ln() - lambda - math::ln_factorial(k as f64)).exp()
+        });
+    }
+}
+
+#[test]
+fn test_poisson_large_lambda() {
+    let lambda = 1e12;
+    let samples = draw_counts(&Poisson::new(lambda).unwrap(), 50_000);
+    assert_moments(&as_f64(&samples), lambda, lambda, 0.0);
+    assert_eq!(Poisson::new(0.0), Err(PoissonError::BadLambda));
+    assert_eq!(Poisson::new(f64::NAN), Err(PoissonError::BadLambda));
+    assert_eq!(Poisson::new(1e300), Err(PoissonError::BadLambda));
+}
+
+#[test]
+fn test_geometric_fits_pmf() {
+    for p in [0.9, 0.3, 0.01] {
+        let samples = draw_counts(&Geometric::new(p).unwrap(), SAMPLES);
+        let q = 1.0 - p;
+        assert_moments(&as_f64(&samples), q / p, q / (p * p), 6.0 + p * p / q);
+        assert_fits_pmf(&samples, |k| q.powf(k as f64) * p);
+    }
+    let mut rng = Tyche::new().unwrap();
+    assert_eq!(Geometric::new(1.0).unwrap().sample(&mut rng).unwrap(), 0);
+    assert_eq!(Geometric::new(0.0), Err(GeometricError::BadProbability));
+    assert_eq!(Geometric::new(1.5), Err(GeometricError::BadProbability));
+}
+
+#[test]
+fn test_hypergeometric_fits_pmf() {
+    for (total, successes, draws) in [
+        (50, 20, 5),
+        (100, 70, 95),
+        (10_000, 3_000, 500),
+        (10_000, 9_000, 7_000),
+        (1_000_000, 500, 20_000),
+    ] {
+        let samples = draw_counts(
+            &Hypergeometric::new(total, successes, draws).unwrap(),
+            SAMPLES,
+        );
+        assert!(samples.iter().all(|&k| k <= successes.min(draws)));
+        let (nf, kf, df) = (total as f64, successes as f64, draws as f64);
+        let mean = df * kf / nf;
+        let var = mean * (nf - kf) / nf * (nf - df) / (nf - 1.0);
+        assert_moments(&as_f64(&samples), mean, var, 1.0 / var);
+        assert_fits_pmf(&samples, |k| {
+            if k > successes || k > draws || draws - k > total - successes {
+                0.0
+            } else {
+                (ln_choose(successes, k) + ln_choose(total - successes, draws - k)
+                    - ln_choose(total, draws))
+                .exp()
+            }
+        });
+    }
+    assert_eq!(
+        Hypergeometric::new(10, 11, 1),
+        Err(HypergeometricError::TooManySuccesses)
+    );
+    assert_eq!(
+        Hypergeometric::new(10, 1, 11),
+        Err(HypergeometricError::TooManyDraws)
+    );
+}
+
+/// Compare sample quantiles with the analytic CDF: the CDF at the empirical `q` quantile
+/// must be within 6 standard errors, `sqrt(q (1 - q) / n)`, of `q`.
+fn assert_quantiles(samples: &mut [f64], cdf: impl Fn(f64) -> f64) {
+    samples.sort_by(f64::total_cmp);
+    let n = samples.len() as f64;
+    for q in [0.001, 0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.99, 0.999] {
+        let x = samples[(q * n) as usize];
+        let tolerance = 6.0 * (q * (1.0 - q) / n).sqrt();
+        let p = cdf(x);
+        assert!((p - q).abs() < tolerance, "quantile {q} is {x}, CDF {p}");
+    }
+}
+
+fn assert_matches_cdf<D: Distribution<f64>>(distribution: &D, cdf: impl Fn(f64) -> f64) {
+    let mut samples = draw(distribution);
+    assert_quantiles(&mut samples, &cdf);
+    let d = ks_statistic(&mut samples, &cdf);
+    assert!(d < ks_critical(SAMPLES), "KS statistic {d}");
+}
+
+#[test]
+fn test_pareto_matches_cdf() {
+    for (scale, shape) in [(1.0, 0.5), (2.0, 1.16), (0.1, 3.0)] {
+        assert_matches_cdf(&Pareto::new(scale, shape).unwrap(), |x| {
+            1.0 - (scale / x).powf(shape)
+        });
+    }
+    let samples = draw(&Pareto::new(3.0, 2.0).unwrap());
+    assert!(samples.iter().all(|&x| x >= 3.0));
+}
+
+#[test]
+fn test_weibull_matches_cdf() {
+    for (scale, shape) in [(1.0, 0.5), (2.0, 1.0), (0.5, 5.0)] {
+        assert_matches_cdf(&Weibull::new(scale, shape).unwrap(), |x| {
+            1.0 - (-(x / scale).powf(shape)).exp()
+        });
+    }
+}
+
+#[test]
+fn test_log_normal_matches_cdf() {
+    for (mu, sigma) in [(0.0, 1.0), (2.0, 0.25), (-1.0, 2.0)] {
+        assert_matches_cdf(&LogNormal::new(mu, sigma).unwrap(), |x| {
+            normal_cdf(x.ln(), mu, sigma)
+        });
+    }
+}
+
+#[test]
+fn test_cauchy_matches_cdf() {
+    for (median, scale) in [(0.0, 1.0), (-5.0, 0.1), (100.0, 20.0)] {
+        assert_matches_cdf(&Cauchy::new(median, scale).unwrap(), |x| {
+            0.5 + ((x - median) / scale).atan() / std::f64::consts::PI
+        });
+    }
+}
+
+#[test]
+fn test_zipf_fits_pmf() {
+    for (n, s) in [
+        (10, 0.0),
+        (50, 0.5),
+        (100, 1.0),
+        (1000, 1.1),
+        (1_000_000, 2.0),
+    ] {
+        let samples = draw_counts(&Zipf::new(n, s).unwrap(), SAMPLES);
+        assert!(samples.iter().all(|&k| (1..=n).contains(&k)));
+        let norm = (1..=n).map(|k| (k as f64).powf(-s)).sum::<f64>();
+        assert_fits_pmf(&samples, |k| {
+            if k == 0 || k > n {
+                0.0
+            } else {
+                (k as f64).powf(-s) / norm
+            }
+        });
+    }
+    let mut rng = Tyche::new().unwrap();
+    let single = Zipf::new(1, 1.5).unwrap();
+    assert_eq!(single.sample(&mut rng).unwrap(), 1);
+    let huge = Zipf::new(u64::MAX, 1.01).unwrap();
+    assert!(huge.sample(&mut rng).unwrap() >= 1);
+}
+
+#[test]
+fn test_heavy_tailed_f32_samplers() {
+    let mut rng = Tyche::new().unwrap();
+    let x: f32 = Pareto::new(1.0f32, 2.0).unwrap().sample(&mut rng).unwrap();
+    let y: f32 = Weibull::new(1.0f32, 2.0).unwrap().sample(&mut rng).unwrap();
+    let z: f32 = LogNormal::new(0.0f32, 1.0)
+        .unwrap()
+        .sample(&mut rng)
+        .unwrap();
+    let w: f32 = Cauchy::new(0.0f32, 1.0).unwrap().sample(&mut rng).unwrap();
+    assert!(x >= 1.0 && y >= 0.0 && z > 0.0 && w.is_finite());
+}
+
+#[test]
+fn test_heavy_tailed_rejects_bad_parameters() {
+    assert_eq!(Pareto::new(0.0, 1.0), Err(ScaleShapeError::BadScale));
+    assert_eq!(Pareto::new(1.0, f64::NAN), Err(ScaleShapeError::BadShape));
+    assert_eq!(Weibull::new(-1.0, 1.0), Err(ScaleShapeError::BadScale));
+    assert_eq!(Weibull::new(1.0, 0.0), Err(ScaleShapeError::BadShape));
+    assert_eq!(
+        LogNormal::new(f64::NAN, 1.0),
+        Err(NormalError::MeanNotFinite)
+    );
+    assert_eq!(LogNormal::new(0.0, -1.0), Err(NormalError::BadStdDev));
+    assert_eq!(
+        Cauchy::new(f64::INFINITY, 1.0),
+        Err(CauchyError::MedianNotFinite)
+    );
+    assert_eq!(Cauchy::new(0.0, 0.0), Err(CauchyError::BadScale));
+    assert_eq!(Zipf::new(0, 1.0), Err(ZipfError::NoItem));
+    assert_eq!(Zipf::new(10, -0.5), Err(ZipfError::BadExponent));
+    let zipf = Zipf::new(10, 1.5).unwrap();
+    assert_eq!((zipf.n(), zipf.s()), (10, 1.5));
+}
+
+/// Chi-square test that angles in `(-π, π]` are uniform over 16 sectors, 15 degrees of freedom.
+fn assert_uniform_angles(angles: impl Iterator<Item = f64>) {
+    let mut bins = [0.0f64; 16];
+    let mut n = 0.0;
+    for a in angles {
+        let bin = ((a + std::f64::consts::PI) / std::f64::consts::TAU * 16.0) as usize;
+        bins[bin.min(15)] += 1.0;
+        n += 1.0;
+    }
+    let expected = n / 16.0;
+    let stat = bins
+        .iter()
+        .map(|o| (o - expected) * (o - expected) / expected)
+        .sum::<f64>();
+    assert!(stat < 56.49, "chi-square {stat}");
+}
+
+fn draw_points<const N: usize, D: Distribution<[f64; N]>>(distribution: &D) -> Vec<[f64; N]> {
+    let mut rng = Tyche::new().unwrap();
+    (0..SAMPLES)
+        .map(|_| distribution.sample(&mut rng))
+        .collect::<Result<Vec<[f64; N]>, _>>()
+        .unwrap()
+}
+
+fn norm(point: &[f64]) -> f64 {
+    point.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+#[test]
+fn test_unit_circle_is_uniform() {
+    let points = draw_points(&UnitCircle);
+    assert!(points.iter().all(|p| (norm(p) - 1.0).abs() < 1e-12));
+    assert_uniform_angles(points.iter().map(|[x, y]| y.atan2(*x)));
+}
+
+#[test]
+fn test_unit_disc_is_uniform() {
+    let points = draw_points(&UnitDisc);
+    assert!(points.iter().all(|p| norm(p) < 1.0));
+    assert_uniform_angles(points.iter().map(|[x, y]| y.atan2(*x)));
+    // The area within radius r is r^2
+    let mut r2 = points.iter().map(|p| norm(p).powi(2)).collect::<Vec<f64>>();
+    let d = ks_statistic(&mut r2, |x| x);
+    assert!(d < ks_critical(SAMPLES), "KS statistic {d}");
+}
+
+#[test]
+fn test_unit_sphere_is_uniform() {
+    let points = draw_points(&UnitSphere);
+    assert!(points.iter().all(|p| (norm(p) - 1.0).abs() < 1e-12));
+    // Archimedes: every coordinate is uniform in [-1, 1]
+    for axis in 0..3 {
+        let mut coordinate = points.iter().map(|p| p[axis]).collect::<Vec<f64>>();
+        let d = ks_statistic(&mut coordinate, |x| (x + 1.0) / 2.0);
+        assert!(d < ks_critical(SAMPLES), "axis {axis}: KS statistic {d}");
+    }
+    assert_uniform_angles(points.iter().map(|[x, y, _]| y.atan2(*x)));
+}
+
+#[test]
+fn test_unit_ball_is_uniform() {
+    let points = draw_points(&UnitBall);
+    assert!(points.iter().all(|p| norm(p) < 1.0));
+    let mut r3 = points.iter().map(|p| norm(p).powi(3)).collect::<Vec<f64>>();
+    let d = ks_statistic(&mut r3, |x| x);
+    assert!(d < ks_critical(SAMPLES), "KS statistic {d}");
+    assert_uniform_angles(points.iter().map(|[_, y, z]| z.atan2(*y)));
+}
+
+#[test]
+fn test_unit_hypersphere_and_hyperball() {
+    let mut rng = Tyche::new().unwrap();
+    let dimension = 5;
+    let sphere = UnitHypersphere::new(dimension).unwrap();
+    let ball = UnitHyperball::new(dimension).unwrap();
+    let n = 50_000;
+    let mut first = Vec::with_capacity(n);
+    let mut radii = Vec::with_capacity(n);
+    for _ in 0..n {
+        let p = sphere.sample(&mut rng).unwrap();
+        assert_eq!(p.len(), dimension);
+        assert!((norm(&p) - 1.0).abs() < 1e-12);
+        first.push(p[0]);
+        let q = ball.sample(&mut rng).unwrap();
+        radii.push(norm(&q).powi(dimension as i32));
+    }
+    // By symmetry every coordinate has mean 0 and the squares have mean 1 / dimension
+    let (mean, var) = moments(&first);
+    assert!(mean.abs() < 6.0 * (var / n as f64).sqrt(), "mean {mean}");
+    assert!(
+        (var - 1.0 / dimension as f64).abs() < 0.01,
+        "variance {var}"
+    );
+    // The volume within radius r is r^dimension
+    let d = ks_statistic(&mut radii, |x| x);
+    assert!(d < ks_critical(n), "KS statistic {d}");
+    // The 1-sphere is {-1, 1}
+    let line = UnitHypersphere::new(1).unwrap();
+    for _ in 0..100 {
+        assert_eq!(line.sample(&mut rng).unwrap()[0].abs(), 1.0);
+    }
+    assert_eq!(UnitHypersphere::new(0), Err(DimensionError::Zero));
+    assert_eq!(UnitHyperball::new(0), Err(DimensionError::Zero));
+}
+
+#[test]
+fn test_simplex_is_uniform() {
+    let dimension = 4;
+    let simplex = Simplex::new(dimension).unwrap();
+    let mut rng = Tyche::new().unwrap();
+    let mut first = Vec::with_capacity(SAMPLES);
+    for _ in 0..SAMPLES {
+        let p = simplex.sample(&mut rng).unwrap();
+        assert_eq!(p.len(), dimension);
+        assert!(p.iter().all(|&x| x >= 0.0));
+        assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        first.push(p[0]);
+    }
+    // A coordinate of the uniform simplex is Beta(1, dimension - 1)
+    let k = (dimension - 1) as i32;
+    let d = ks_statistic(&mut first, |x| 1.0 - (1.0 - x).powi(k));
+    assert!(d < ks_critical(SAMPLES), "KS statistic {d}");
+    assert_eq!(
+        Simplex::new(1).unwrap().sample(&mut rng).unwrap(),
+        vec![1.0]
+    );
+    assert_eq!(Simplex::new(0), Err(DimensionError::Zero));
+}
+
+#[test]
+fn test_dirichlet_moments() {
+    let alpha = [0.5, 2.0, 7.5];
+    let dirichlet = Dirichlet::new(&alpha).unwrap();
+    let mut rng = Tyche::new().unwrap();
+    let mut coordinates = vec![Vec::with_capacity(SAMPLES); alpha.len()];
+    for _ in 0..SAMPLES {
+        let p = dirichlet.sample(&mut rng).unwrap();
+        assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        for (c, x) in coordinates.iter_mut().zip(p) {
+            c.push(x);
+        }
+    }
+    // Every coordinate is Beta(alpha_i, alpha_0 - alpha_i)
+    let a0 = alpha.iter().sum::<f64>();
+    for (&a, samples) in alpha.iter().zip(&coordinates) {
+        let b = a0 - a;
+        let mean = a / a0;
+        let var = a * b / (a0 * a0 * (a0 + 1.0));
+        let excess = 6.0 * ((a - b) * (a - b) * (a0 + 1.0) - a * b * (a0 + 2.0))
+            / (a * b * (a0 + 2.0) * (a0 + 3.0));
+        assert_moments(samples, mean, var, excess);
+    }
+}
+
+#[test]
+fn test_dirichlet_tiny_alphas_stay_on_the_simplex() {
+    let dirichlet = Dirichlet::new(&[1e-4, 1e-4, 1e-4]).unwrap();
+    let mut rng = Tyche::new().unwrap();
+    for _ in 0..10_000 {
+        let p = dirichlet.sample(&mut rng).unwrap();
+        assert!(p.iter().all(|x| (0.0..=1.0).contains(x)), "{p:?}");
+        assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+    assert_eq!(Dirichlet::new(&[]), Err(DirichletError::NoItem));
+    assert_eq!(
+        Dirichlet::new(&[1.0, 0.0]),
+        Err(DirichletError::BadAlpha { index: 1 })
+    );
+    assert_eq!(Dirichlet::new(&[2.0, 3.0]).unwrap().alpha(), &[2.0, 3.0]);
+}
@@ -0,0 +1,195 @@
+//! Uniform points on and inside unit spheres.
+
+use super::Distribution;
+use super::ziggurat::standard_normal;
+use crate::RngApiExt;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Uniform in `[-1, 1)`.
+fn symmetric_unit<R: RngApi + ?Sized>(rng: &mut R) -> RngResult<f64> {
+    Ok(2.0 * rng.random_unit_f64()? - 1.0)
+}
+
+/// A point uniformly distributed inside the unit disc, and its squared norm, by rejection
+/// from the enclosing square. Accepts `π / 4` of all candidates.
+fn in_disc<R: RngApi + ?Sized>(rng: &mut R) -> RngResult<([f64; 2], f64)> {
+    loop {
+        let x = symmetric_unit(rng)?;
+        let y = symmetric_unit(rng)?;
+        let s = x * x + y * y;
+        if s < 1.0 {
+            return Ok(([x, y], s));
+        }
+    }
+}
+
+/// Uniformly distributed points on the unit circle, as `[x, y]`.
+///
+/// Uses von Neumann's method, squaring a random point of the unit disc as a complex number,
+/// which needs no trigonometry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnitCircle;
+
+impl Distribution<[f64; 2]> for UnitCircle {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<[f64; 2]> {
+        loop {
+            let ([x, y], s) = in_disc(rng)?;
+            // The origin has no direction
+            if s > 0.0 {
+                return Ok([(x * x - y * y) / s, 2.0 * x * y / s]);
+            }
+        }
+    }
+}
+
+/// Uniformly distributed points inside the unit disc, as `[x, y]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnitDisc;
+
+impl Distribution<[f64; 2]> for UnitDisc {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<[f64; 2]> {
+        Ok(in_disc(rng)?.0)
+    }
+}
+
+/// Uniformly distributed points on the surface of the unit sphere, as `[x, y, z]`.
+///
+/// Uses Marsaglia's method, "Choosing a Point from the Surface of a Sphere", 1972, which maps
+/// a random point of the unit disc onto the sphere.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnitSphere;
+
+impl Distribution<[f64; 3]> for UnitSphere {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<[f64; 3]> {
+        let ([x, y], s) = in_disc(rng)?;
+        let factor = 2.0 * (1.0 - s).sqrt();
+        Ok([x * factor, y * factor, 1.0 - 2.0 * s])
+    }
+}
+
+/// Uniformly distributed points inside the unit ball, as `[x, y, z]`.
+///
+/// Rejection from the enclosing cube, accepting `π / 6` of all candidates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnitBall;
+
+impl Distribution<[f64; 3]> for UnitBall {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<[f64; 3]> {
+        loop {
+            let x = symmetric_unit(rng)?;
+            let y = symmetric_unit(rng)?;
+            let z = symmetric_unit(rng)?;
+            if x * x + y * y + z * z < 1.0 {
+                return Ok([x, y, z]);
+            }
+        }
+    }
+}
+
+/// Why an n-dimensional distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DimensionError {
+    /// The dimension is 0.
+    Zero,
+}
+
+impl Display for DimensionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            DimensionError::Zero => write!(f, "The dimension must be at least 1!"),
+        }
+    }
+}
+
+impl Error for DimensionError {}
+
+impl From<DimensionError> for RngError {
+    fn from(e: DimensionError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// Uniformly distributed points on the surface of the unit sphere in `dimension` dimensions.
+///
+/// A vector of independent standard normal samples points in a uniformly random direction,
+/// and is normalised to length 1. In one dimension the "sphere" is `{-1, 1}`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnitHypersphere {
+    dimension: usize,
+}
+
+impl UnitHypersphere {
+    /// Create the distribution for the given number of coordinates.
+    ///
+    /// ## Errors
+    /// Returns `DimensionError` if `dimension` is 0.
+    pub fn new(dimension: usize) -> Result<Self, DimensionError> {
+        if dimension == 0 {
+            return Err(DimensionError::Zero);
+        }
+        Ok(UnitHypersphere { dimension })
+    }
+
+    /// The number of coordinates.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+}
+
+impl Distribution<Vec<f64>> for UnitHypersphere {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<Vec<f64>> {
+        let mut point = vec![0.0; self.dimension];
+        loop {
+            let mut norm = 0.0;
+            for x in point.iter_mut() {
+                *x = standard_normal(rng)?;
+                norm += *x * *x;
+            }
+            // Only 0 for a vector of exact zeros, which has no direction
+            if norm > 0.0 {
+                let norm = norm.sqrt();
+                point.iter_mut().for_each(|x| *x /= norm);
+                return Ok(point);
+            }
+        }
+    }
+}
+
+/// Uniformly distributed points inside the unit ball in `dimension` dimensions.
+///
+/// A point of the unit hypersphere, scaled by `U^(1 / dimension)` so that every shell gets
+/// its share of the volume. Unlike rejection from the cube, this stays fast in high dimensions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnitHyperball {
+    sphere: UnitHypersphere,
+}
+
+impl UnitHyperball {
+    /// Create the distribution for the given number of coordinates.
+    ///
+    /// ## Errors
+    /// Returns `DimensionError` if `dimension` is 0.
+    pub fn new(dimension: usize) -> Result<Self, DimensionError> {
+        Ok(UnitHyperball {
+            sphere: UnitHypersphere::new(dimension)?,
+        })
+    }
+
+    /// The number of coordinates.
+    pub fn dimension(&self) -> usize {
+        self.sphere.dimension
+    }
+}
+
+impl Distribution<Vec<f64>> for UnitHyperball {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<Vec<f64>> {
+        let mut point = self.sphere.sample(rng)?;
+        let radius = rng
+            .random_unit_f64()?
+            .powf(1.0 / self.sphere.dimension as f64);
+        point.iter_mut().for_each(|x| *x *= radius);
+        Ok(point)
+    }
+}
@@ -0,0 +1,83 @@
+//! The Ziggurat method for the standard normal distribution.
+//!
+//! The density is covered by 256 horizontal layers of equal area: a base layer holding the
+//! tail beyond `R`, and 255 rectangles stacked on top of it. A sample picks a layer and a
+//! point inside its rectangle; nearly always the point lies under the curve outright and is
+//! returned after one multiplication and one comparison. Only points in the sliver between
+//! a rectangle's edge and the curve need the density evaluated, and the base layer falls back
+//! to Marsaglia's tail algorithm.
+//!
+//! See Marsaglia & Tsang, "The Ziggurat Method for Generating Random Variables", JSS 2000.
+//! The low 8 bits of the random word pick the layer and the top 53 the point, so the two
+//! are independent (Doornik, "An Improved Ziggurat Method", 2005).
+
+use crate::RngApiExt;
+use athena::rng_api::{RngApi, RngResult};
+use std::sync::OnceLock;
+
+const LAYERS: usize = 256;
+/// Where the tail begins.
+const R: f64 = 3.654_152_885_361_009;
+/// The area of every layer.
+const V: f64 = 0.004_928_673_233_99;
+
+/// Unnormalised standard normal density.
+fn pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp()
+}
+
+struct Tables {
+    /// Right edge of every layer's rectangle, from the base upward; `x[LAYERS]` is 0.
+    x: [f64; LAYERS + 1],
+    /// `pdf(x[i])`.
+    f: [f64; LAYERS + 1],
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut x = [0.0; LAYERS + 1];
+        // The base layer is a rectangle of the same area as the others, it only reaches
+        // beyond `R` to account for the tail.
+        x[0] = V / pdf(R);
+        x[1] = R;
+        for i in 2..LAYERS {
+            x[i] = (-2.0 * (V / x[i - 1] + pdf(x[i - 1])).ln()).sqrt();
+        }
+        x[LAYERS] = 0.0;
+        let f = x.map(pdf);
+        Tables { x, f }
+    })
+}
+
+/// Draw from the standard normal distribution.
+pub(crate) fn standard_normal<R: RngApi + ?Sized>(rng: &mut R) -> RngResult<f64> {
+    let Tables { x: xs, f: fs } = tables();
+    loop {
+        let bits = rng.random_u64()?;
+        let i = (bits & 0xff) as usize;
+        // Uniform in (-1, 1), never exactly 0 or 1
+        let u = ((bits >> 11) as f64 + 0.5) * (2.0 / (1u64 << 53) as f64) - 1.0;
+        let x = u * xs[i];
+        if x.abs() < xs[i + 1] {
+            return Ok(x);
+        }
+        if i == 0 {
+            return tail(rng, u < 0.0);
+        }
+        if fs[i + 1] + (fs[i] - fs[i + 1]) * rng.random_unit_f64()? < pdf(x) {
+            return Ok(x);
+        }
+    }
+}
+
+/// Marsaglia's algorithm for the normal tail beyond `R`.
+fn tail<G: RngApi + ?Sized>(rng: &mut G, negative: bool) -> RngResult<f64> {
+    loop {
+        let a = -rng.random_unit_f64_open_closed()?.ln() / R;
+        let b = -rng.random_unit_f64_open_closed()?.ln();
+        if b + b >= a * a {
+            return Ok(if negative { -(R + a) } else { R + a });
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! The Zipf distribution.
+
+use super::Distribution;
+use crate::RngApiExt;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Why a [`Zipf`] distribution could not be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZipfError {
+    /// There are no elements to choose from.
+    NoItem,
+    /// The exponent is negative, NaN or infinite.
+    BadExponent,
+}
+
+impl Display for ZipfError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ZipfError::NoItem => write!(f, "The number of elements must be at least 1!"),
+            ZipfError::BadExponent => {
+                write!(f, "The exponent must be finite and non-negative!")
+            }
+        }
+    }
+}
+
+impl Error for ZipfError {}
+
+impl From<ZipfError> for RngError {
+    fn from(e: ZipfError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// The Zipf distribution over the ranks `1..=n`, rank `k` having probability proportional
+/// to `k^-s`.
+///
+/// Models the popularity of keys, words or pages. Sampled with the rejection-inversion method
+/// of Hörmann & Derflinger, "Rejection-inversion to generate variates from monotone discrete
+/// distributions", 1996: the ranks are inverted through the integral of a continuous hat
+/// function and rarely rejected. Construction and sampling take constant time, whatever `n`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Zipf {
+    n: u64,
+    s: f64,
+    /// `H(1.5) - 1`, the upper end of the inverted interval.
+    h_x1: f64,
+    /// `H(n + 0.5)`, its lower end.
+    h_n: f64,
+    /// Ranks closer than this to their continuous preimage are accepted without a test.
+    accept: f64,
+}
+
+impl Zipf {
+    /// Create a Zipf distribution over `1..=n` with exponent `s`; `s = 0` is uniform.
+    ///
+    /// ## Errors
+    /// Returns `ZipfError` if `n` is 0 or `s` is negative or not finite.
+    pub fn new(n: u64, s: f64) -> Result<Self, ZipfError> {
+        if n == 0 {
+            return Err(ZipfError::NoItem);
+        }
+        if !(s.is_finite() && s >= 0.0) {
+            return Err(ZipfError::BadExponent);
+        }
+        let mut zipf = Zipf {
+            n,
+            s,
+            h_x1: 0.0,
+            h_n: 0.0,
+            accept: 0.0,
+        };
+        zipf.h_x1 = zipf.h_integral(1.5) - 1.0;
+        zipf.h_n = zipf.h_integral(n as f64 + 0.5);
+        zipf.accept = 2.0 - zipf.h_integral_inverse(zipf.h_integral(2.5) - zipf.h(2.0));
+        Ok(zipf)
+    }
+
+    /// The number of ranks.
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    /// The exponent.
+    pub fn s(&self) -> f64 {
+        self.s
+    }
+
+    /// The hat function, `x^-s`.
+    fn h(&self, x: f64) -> f64 {
+        (-self.s * x.ln()).exp()
+    }
+
+    /// `H(x) = (x^(1 - s) - 1) / (1 - s)`, an antiderivative of `h`, `ln(x)` for `s = 1`.
+    fn h_integral(&self, x: f64) -> f64 {
+        let ln_x = x.ln();
+        exp_m1_over_x((1.0 - self.s) * ln_x) * ln_x
+    }
+
+    /// The inverse of `H`.
+    fn h_integral_inverse(&self, x: f64) -> f64 {
+        // Clamp rounding errors that would leave the domain of ln(1 + t)
+        let t = (x * (1.0 - self.s)).max(-1.0);
+        (ln_1p_over_x(t) * x).exp()
+    }
+}
+
+/// `ln(1 + x) / x`, continuous at 0.
+fn ln_1p_over_x(x: f64) -> f64 {
+    if x.abs() > 1e-8 {
+        x.ln_1p() / x
+    } else {
+        1.0 - x * (0.5 - x * (1.0 / 3.0 - 0.25 * x))
+    }
+}
+
+/// `(e^x - 1) / x`, continuous at 0.
+fn exp_m1_over_x(x: f64) -> f64 {
+    if x.abs() > 1e-8 {
+        x.exp_m1() / x
+    } else {
+        1.0 + x * 0.5 * (1.0 + x / 3.0 * (1.0 + 0.25 * x))
+    }
+}
+
+impl Distribution<u64> for Zipf {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<u64> {
+        loop {
+            let u = self.h_n + rng.random_unit_f64()? * (self.h_x1 - self.h_n);
+            let x = self.h_integral_inverse(u);
+            // Round to the nearest rank, `as` saturates
+            let k = ((x + 0.5) as u64).clamp(1, self.n);
+            let kf = k as f64;
+            if kf - x <= self.accept || u >= self.h_integral(kf + 0.5) - self.h(kf) {
+                return Ok(k);
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! AES-256 encryption, as specified in FIPS 197.
+//!
+//! Only the forward cipher is implemented, which is all CTR_DRBG needs. The S-box is computed
+//! with the Boyar–Peralta circuit on bitsliced bytes instead of a table lookup, so no memory
+//! access depends on secret data.
+
+use crate::pool::wipe;
+
+pub(crate) const BLOCK_LEN: usize = 16;
+pub(crate) const KEY_LEN: usize = 32;
+const ROUNDS: usize = 14;
+
+/// An expanded AES-256 key. The round keys are wiped on drop.
+pub(crate) struct Aes256 {
+    round_keys: [[u8; BLOCK_LEN]; ROUNDS + 1],
+}
+
+impl Aes256 {
+    pub(crate) fn new(key: &[u8; KEY_LEN]) -> Self {
+        let mut words = [[0u8; 4]; 4 * (ROUNDS + 1)];
+        for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+            word.copy_from_slice(chunk);
+        }
+        let mut rcon = 1u8;
+        for i in 8..words.len() {
+            let mut temp = words[i - 1];
+            if i % 8 == 0 {
+                temp.rotate_left(1);
+                sub_word(&mut temp);
+                temp[0] ^= rcon;
+                rcon = xtime(rcon);
+            } else if i % 8 == 4 {
+                sub_word(&mut temp);
+            }
+            for (byte, previous) in temp.iter_mut().zip(words[i - 8]) {
+                *byte ^= previous;
+            }
+            words[i] = temp;
+        }
+
+        let mut round_keys = [[0u8; BLOCK_LEN]; ROUNDS + 1];
+        for (round_key, chunk) in round_keys.iter_mut().zip(words.chunks_exact(4)) {
+            for (bytes, word) in round_key.chunks_exact_mut(4).zip(chunk) {
+                bytes.copy_from_slice(word);
+            }
+        }
+        wipe(words.as_flattened_mut());
+        Aes256 { round_keys }
+    }
+
+    pub(crate) fn encrypt(&self, block: &mut [u8; BLOCK_LEN]) {
+        add_round_key(block, &self.round_keys[0]);
+        for round_key in &self.round_keys[1..ROUNDS] {
+            sub_bytes(block);
+            shift_rows(block);
+            mix_columns(block);
+            add_round_key(block, round_key);
+        }
+        sub_bytes(block);
+        shift_rows(block);
+        add_round_key(block, &self.round_keys[ROUNDS]);
+    }
+}
+
+impl Drop for Aes256 {
+    fn drop(&mut self) {
+        wipe(self.round_keys.as_flattened_mut());
+    }
+}
+
+fn add_round_key(block: &mut [u8; BLOCK_LEN], round_key: &[u8; BLOCK_LEN]) {
+    for (byte, key) in block.iter_mut().zip(round_key) {
+        *byte ^= key;
+    }
+}
+
+/// Multiplication by `x` in GF(2^8), without a branch on the top bit.
+fn xtime(b: u8) -> u8 {
+    (b << 1) ^ ((b >> 7) * 0x1b)
+}
+
+/// The state is stored column by column, so row `r` of column `c` is byte `4c + r`.
+fn shift_rows(block: &mut [u8; BLOCK_LEN]) {
+    let old = *block;
+    for column in 0..4 {
+        for row in 1..4 {
+            block[4 * column + row] = old[4 * ((column + row) % 4) + row];
+        }
+    }
+}
+
+fn mix_columns(block: &mut [u8; BLOCK_LEN]) {
+    for column in block.chunks_exact_mut(4) {
+        let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+        let all = a0 ^ a1 ^ a2 ^ a3;
+        column[0] ^= all ^ xtime(a0 ^ a1);
+        column[1] ^= all ^ xtime(a1 ^ a2);
+        column[2] ^= all ^ xtime(a2 ^ a3);
+        column[3] ^= all ^ xtime(a3 ^ a0);
+    }
+}
+
+fn sub_word(word: &mut [u8; 4]) {
+    let mut block = [0u8; BLOCK_LEN];
+    block[..4].copy_from_slice(word);
+    sub_bytes(&mut block);
+    word.copy_from_slice(&block[..4]);
+}
+
+/// Apply the S-box to all 16 bytes at once: transpose them into eight 16 bit words holding
+/// one bit of every byte each, run the circuit, and transpose back.
+fn sub_bytes(block: &mut [u8; BLOCK_LEN]) {
+    let mut q = [0u16; 8];
+    for (i, &byte) in block.iter().enumerate() {
+        for (bit, word) in q.iter_mut().enumerate() {
+            *word |= ((byte >> bit) as u16 & 1) << i;
+        }
+    }
+    sbox_circuit(&mut q);
+    for (i, byte) in block.iter_mut().enumerate() {
+        *byte = 0;
+        for (bit, word) in q.iter().enumerate() {
+            *byte |= ((word >> i) as u8 & 1) << bit;
+        }
+    }
+}
+
+/// The 113 gate S-box circuit of Boyar and Peralta, "A depth-16 circuit for the AES S-box",
+/// 2011. `q[i]` holds bit `i` of each input byte and receives bit `i` of each output.
+fn sbox_circuit(q: &mut [u16; 8]) {
+    let [x7, x6, x5, x4, x3, x2, x1, x0] = *q;
+
+    // Top linear transformation
+    let y14 = x3 ^ x5;
+    let y13 = x0 ^ x6;
+    let y9 = x0 ^ x3;
+    let y8 = x0 ^ x5;
+    let t0 = x1 ^ x2;
+    let y1 = t0 ^ x7;
+    let y4 = y1 ^ x3;
+    let y12 = y13 ^ y14;
+    let y2 = y1 ^ x0;
+    let y5 = y1 ^ x6;
+    let y3 = y5 ^ y8;
+    let t1 = x4 ^ y12;
+    let y15 = t1 ^ x5;
+    let y20 = t1 ^ x1;
+    let y6 = y15 ^ x7;
+    let y10 = y15 ^ t0;
+    let y11 = y20 ^ y9;
+    let y7 = x7 ^ y11;
+    let y17 = y10 ^ y11;
+    let y19 = y10 ^ y8;
+    let y16 = t0 ^ y11;
+    let y21 = y13 ^ y16;
+    let y18 = x0 ^ y16;
+
+    // Non-linear section
+    let t2 = y12 & y15;
+    let t3 = y3 & y6;
+    let t4 = t3 ^ t2;
+    let t5 = y4 & x7;
+    let t6 = t5 ^ t2;
+    let t7 = y13 & y16;
+    let t8 = y5 & y1;
+    let t9 = t8 ^ t7;
+    let t10 = y2 & y7;
+    let t11 = t10 ^ t7;
+    let t12 = y9 & y11;
+    let t13 = y14 & y17;
+    let t14 = t13 ^ t12;
+    let t15 = y8 & y10;
+    let t16 = t15 ^ t12;
+    let t17 = t4 ^ t14;
+    let t18 = t6 ^ t16;
+    let t19 = t9 ^ t14;
+    let t20 = t11 ^ t16;
+    let t21 = t17 ^ y20;
+    let t22 = t18 ^ y19;
+    let t23 = t19 ^ y21;
+    let t24 = t20 ^ y18;
+
+    let t25 = t21 ^ t22;
+    let t26 = t21 & t23;
+    let t27 = t24 ^ t26;
+    let t28 = t25 & t27;
+    let t29 = t28 ^ t22;
+    let t30 = t23 ^ t24;
+    let t31 = t22 ^ t26;
+    let t32 = t31 & t30;
+    let t33 = t32 ^ t24;
+    let t34 = t23 ^ t33;
+    let t35 = t27 ^ t33;
+    let t36 = t24 & t35;
+    let t37 = t36 ^ t34;
+    let t38 = t27 ^ t36;
+    let t39 = t29 & t38;
+    let t40 = t25 ^ t39;
+
+    let t41 = t40 ^ t37;
+    let t42 = t29 ^ t33;
+    let t43 = t29 ^ t40;
+    let t44 = t33 ^ t37;
+    let t45 = t42 ^ t41;
+    let z0 = t44 & y15;
+    let z1 = t37 & y6;
+    let z2 = t33 & x7;
+    let z3 = t43 & y16;
+    let z4 = t40 & y1;
+    let z5 = t29 & y7;
+    let z6 = t42 & y11;
+    let z7 = t45 & y17;
+    let z8 = t41 & y10;
+    let z9 = t44 & y12;
+    let z10 = t37 & y3;
+    let z11 = t33 & y4;
+    let z12 = t43 & y13;
+    let z13 = t40 & y5;
+    let z14 = t29 & y2;
+    let z15 = t42 & y9;
+    let z16 = t45 & y14;
+    let z17 = t41 & y8;
+
+    // Bottom linear transformation
+    let t46 = z15 ^ z16;
+    let t47 = z10 ^ z11;
+    let t48 = z5 ^ z13;
+    let t49 = z9 ^ z10;
+    let t50 = z2 ^ z12;
+    let t51 = z2 ^ z5;
+    let t52 = z7 ^ z8;
+    let t53 = z0 ^ z3;
+    let t54 = z6 ^ z7;
+    let t55 = z16 ^ z17;
+    let t56 = z12 ^ t48;
+    let t57 = t50 ^ t53;
+    let t58 = z4 ^ t46;
+    let t59 = z3 ^ t54;
+    let t60 = t46 ^ t57;
+    let t61 = z14 ^ t57;
+    let t62 = t52 ^ t58;
+    let t63 = t49 ^ t58;
+    let t64 = z4 ^ t59;
+    let t65 = t61 ^ t62;
+    let t66 = z1 ^ t63;
+    let s0 = t59 ^ t63;
+    let s6 = t56 ^ !t62;
+    let s7 = t48 ^ !t60;
+    let t67 = t64 ^ t65;
+    let s3 = t53 ^ t66;
+    let s4 = t51 ^ t66;
+    let s5 = t47 ^ t65;
+    let s1 = t64 ^ !s3;
+    let s2 = t55 ^ !t67;
+
+    *q = [s7, s6, s5, s4, s3, s2, s1, s0];
+}
@@ -0,0 +1,187 @@
+//! CTR_DRBG with AES-256 and the derivation function, SP 800-90A section 10.2.1.
+
+use super::aes::{Aes256, BLOCK_LEN, KEY_LEN};
+use super::{Drbg, DrbgError, ReseedCounter, check_inputs, fill_generated};
+use crate::macros::rng_api_methods;
+use crate::pool::wipe;
+use athena::rng_api::{RngApi, RngResult};
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+/// The length of the provided data of an update, key and counter together.
+const SEED_LEN: usize = KEY_LEN + BLOCK_LEN;
+
+/// CTR_DRBG with AES-256, using the derivation function.
+///
+/// The state is an AES key and a 128 bit counter `V`; output is the encryption of successive
+/// counter values. The derivation function compresses inputs of any length into a seed, so
+/// the entropy input need not be full entropy and a nonce is used.
+pub struct CtrDrbg {
+    key: [u8; KEY_LEN],
+    v: [u8; BLOCK_LEN],
+    reseed_counter: ReseedCounter,
+}
+
+impl CtrDrbg {
+    /// CTR_DRBG_Update, replacing key and `V` with the next `SEED_LEN` bytes of keystream
+    /// xored with `provided`.
+    fn update(&mut self, provided: &[u8; SEED_LEN]) {
+        let cipher = Aes256::new(&self.key);
+        let mut temp = [0u8; SEED_LEN];
+        for chunk in temp.chunks_exact_mut(BLOCK_LEN) {
+            increment(&mut self.v);
+            let mut block = self.v;
+            cipher.encrypt(&mut block);
+            chunk.copy_from_slice(&block);
+            wipe(&mut block);
+        }
+        for (byte, p) in temp.iter_mut().zip(provided) {
+            *byte ^= p;
+        }
+        self.key.copy_from_slice(&temp[..KEY_LEN]);
+        self.v.copy_from_slice(&temp[KEY_LEN..]);
+        wipe(&mut temp);
+    }
+
+    fn fill(&mut self, buffer: &mut [u8]) -> RngResult<()> {
+        let forked = self.reseed_counter.forked();
+        fill_generated(self, forked, buffer)
+    }
+}
+
+impl Drbg for CtrDrbg {
+    fn instantiate(
+        entropy_input: &[u8],
+        nonce: &[u8],
+        personalization: &[u8],
+    ) -> Result<Self, DrbgError> {
+        check_inputs(entropy_input, Some(nonce), &[personalization])?;
+        let mut drbg = CtrDrbg {
+            key: [0; KEY_LEN],
+            v: [0; BLOCK_LEN],
+            reseed_counter: ReseedCounter::new(),
+        };
+        let mut seed = block_cipher_df(&[entropy_input, nonce, personalization]);
+        drbg.update(&seed);
+        wipe(&mut seed);
+        Ok(drbg)
+    }
+
+    fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        check_inputs(entropy_input, None, &[additional_input])?;
+        let mut seed = block_cipher_df(&[entropy_input, additional_input]);
+        self.update(&seed);
+        wipe(&mut seed);
+        self.reseed_counter.reset();
+        Ok(())
+    }
+
+    fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        self.reseed_counter.check(output, additional_input)?;
+        let mut additional = [0u8; SEED_LEN];
+        if !additional_input.is_empty() {
+            additional = block_cipher_df(&[additional_input]);
+            self.update(&additional);
+        }
+
+        let cipher = Aes256::new(&self.key);
+        for chunk in output.chunks_mut(BLOCK_LEN) {
+            increment(&mut self.v);
+            let mut block = self.v;
+            cipher.encrypt(&mut block);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            wipe(&mut block);
+        }
+        drop(cipher);
+
+        self.update(&additional);
+        wipe(&mut additional);
+        self.reseed_counter.counter += 1;
+        Ok(())
+    }
+
+    fn reseed_counter(&self) -> u64 {
+        self.reseed_counter.counter
+    }
+
+    fn reseed_interval(&self) -> u64 {
+        self.reseed_counter.interval
+    }
+
+    fn set_reseed_interval(&mut self, interval: u64) -> Result<(), DrbgError> {
+        self.reseed_counter.set_interval(interval)
+    }
+}
+
+impl RngApi for CtrDrbg {
+    rng_api_methods!();
+}
+
+impl Debug for CtrDrbg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "CtrDrbg {{ .. }}")
+    }
+}
+
+impl Drop for CtrDrbg {
+    fn drop(&mut self) {
+        wipe(&mut self.key);
+        wipe(&mut self.v);
+    }
+}
+
+/// Increment the 128 bit big endian counter `v`, wrapping around.
+fn increment(v: &mut [u8; BLOCK_LEN]) {
+    let value = u128::from_be_bytes(*v).wrapping_add(1);
+    *v = value.to_be_bytes();
+}
+
+/// Block_Cipher_df, the derivation function, returning `SEED_LEN` bytes derived from the
+/// concatenation of `input`.
+fn block_cipher_df(input: &[&[u8]]) -> [u8; SEED_LEN] {
+    // S = L || N || input || 0x80, zero padded to whole blocks
+    let length: usize = input.iter().map(|part| part.len()).sum();
+    let mut s = Vec::with_capacity(length + 9 + BLOCK_LEN);
+    s.extend_from_slice(&(length as u32).to_be_bytes());
+    s.extend_from_slice(&(SEED_LEN as u32).to_be_bytes());
+    for part in input {
+        s.extend_from_slice(part);
+    }
+    s.push(0x80);
+    s.resize(s.len().next_multiple_of(BLOCK_LEN), 0);
+
+    let mut df_key = [0u8; KEY_LEN];
+    for (i, byte) in df_key.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let cipher = Aes256::new(&df_key);
+    let mut temp = [0u8; SEED_LEN];
+    for (i, chunk) in (0u32..).zip(temp.chunks_exact_mut(BLOCK_LEN)) {
+        let mut iv = [0u8; BLOCK_LEN];
+        iv[..4].copy_from_slice(&i.to_be_bytes());
+        let mut chaining = bcc_step(&cipher, [0; BLOCK_LEN], &iv);
+        for block in s.chunks_exact(BLOCK_LEN) {
+            chaining = bcc_step(&cipher, chaining, block);
+        }
+        chunk.copy_from_slice(&chaining);
+    }
+    wipe(&mut s);
+
+    let cipher = Aes256::new(temp[..KEY_LEN].try_into().unwrap());
+    let mut x: [u8; BLOCK_LEN] = temp[KEY_LEN..].try_into().unwrap();
+    for chunk in temp.chunks_exact_mut(BLOCK_LEN) {
+        cipher.encrypt(&mut x);
+        chunk.copy_from_slice(&x);
+    }
+    wipe(&mut x);
+    temp
+}
+
+/// One step of BCC, CBC-MAC without an IV.
+fn bcc_step(cipher: &Aes256, chaining: [u8; BLOCK_LEN], block: &[u8]) -> [u8; BLOCK_LEN] {
+    let mut next = chaining;
+    for (byte, b) in next.iter_mut().zip(block) {
+        *byte ^= b;
+    }
+    cipher.encrypt(&mut next);
+    next
+}
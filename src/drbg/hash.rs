@@ -0,0 +1,144 @@
+//! Hash_DRBG with SHA-256, SP 800-90A section 10.1.1.
+
+use super::sha256::{DIGEST_LEN, Sha256};
+use super::{Drbg, DrbgError, ReseedCounter, check_inputs, fill_generated};
+use crate::macros::rng_api_methods;
+use crate::pool::wipe;
+use athena::rng_api::{RngApi, RngResult};
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+/// The length of `V` and `C`, 440 bits for SHA-256.
+const SEED_LEN: usize = 55;
+
+/// Hash_DRBG with SHA-256.
+///
+/// The state is a value `V` that is hashed to produce output and then advanced by a second
+/// secret value `C`. The fastest of the three generators for small requests.
+pub struct HashDrbg {
+    v: [u8; SEED_LEN],
+    c: [u8; SEED_LEN],
+    reseed_counter: ReseedCounter,
+}
+
+impl HashDrbg {
+    /// Derive `V` from `seed_material` and `C` from `V`, resetting the reseed counter.
+    fn seed(&mut self, seed_material: &[&[u8]]) {
+        hash_df(&mut self.v, seed_material);
+        hash_df(&mut self.c, &[&[0x00], &self.v]);
+        self.reseed_counter.reset();
+    }
+
+    fn fill(&mut self, buffer: &mut [u8]) -> RngResult<()> {
+        let forked = self.reseed_counter.forked();
+        fill_generated(self, forked, buffer)
+    }
+}
+
+impl Drbg for HashDrbg {
+    fn instantiate(
+        entropy_input: &[u8],
+        nonce: &[u8],
+        personalization: &[u8],
+    ) -> Result<Self, DrbgError> {
+        check_inputs(entropy_input, Some(nonce), &[personalization])?;
+        let mut drbg = HashDrbg {
+            v: [0; SEED_LEN],
+            c: [0; SEED_LEN],
+            reseed_counter: ReseedCounter::new(),
+        };
+        drbg.seed(&[entropy_input, nonce, personalization]);
+        Ok(drbg)
+    }
+
+    fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        check_inputs(entropy_input, None, &[additional_input])?;
+        let mut v = self.v;
+        self.seed(&[&[0x01], &v, entropy_input, additional_input]);
+        wipe(&mut v);
+        Ok(())
+    }
+
+    fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        self.reseed_counter.check(output, additional_input)?;
+        if !additional_input.is_empty() {
+            let mut w = Sha256::digest(&[&[0x02], &self.v, additional_input]);
+            add_into(&mut self.v, &w);
+            wipe(&mut w);
+        }
+
+        // Hashgen: hash successive values starting at V
+        let mut data = self.v;
+        for chunk in output.chunks_mut(DIGEST_LEN) {
+            let mut block = Sha256::digest(&[&data]);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            wipe(&mut block);
+            add_into(&mut data, &[1]);
+        }
+        wipe(&mut data);
+
+        let mut h = Sha256::digest(&[&[0x03], &self.v]);
+        add_into(&mut self.v, &h);
+        add_into(&mut self.v, &self.c);
+        wipe(&mut h);
+        add_into(&mut self.v, &self.reseed_counter.counter.to_be_bytes());
+        self.reseed_counter.counter += 1;
+        Ok(())
+    }
+
+    fn reseed_counter(&self) -> u64 {
+        self.reseed_counter.counter
+    }
+
+    fn reseed_interval(&self) -> u64 {
+        self.reseed_counter.interval
+    }
+
+    fn set_reseed_interval(&mut self, interval: u64) -> Result<(), DrbgError> {
+        self.reseed_counter.set_interval(interval)
+    }
+}
+
+impl RngApi for HashDrbg {
+    rng_api_methods!();
+}
+
+impl Debug for HashDrbg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "HashDrbg {{ .. }}")
+    }
+}
+
+impl Drop for HashDrbg {
+    fn drop(&mut self) {
+        wipe(&mut self.v);
+        wipe(&mut self.c);
+    }
+}
+
+/// Hash_df, the hash derivation function, writing `SEED_LEN` bytes derived from the
+/// concatenation of `input` into `output`.
+fn hash_df(output: &mut [u8; SEED_LEN], input: &[&[u8]]) {
+    let bits = (SEED_LEN as u32 * 8).to_be_bytes();
+    for (counter, chunk) in (1u8..).zip(output.chunks_mut(DIGEST_LEN)) {
+        let mut hasher = Sha256::new();
+        hasher.update(&[counter]);
+        hasher.update(&bits);
+        for part in input {
+            hasher.update(part);
+        }
+        let mut block = hasher.finish();
+        chunk.copy_from_slice(&block[..chunk.len()]);
+        wipe(&mut block);
+    }
+}
+
+/// `value = (value + addend) mod 2^440`, both big endian, `addend` at most `SEED_LEN` bytes.
+fn add_into(value: &mut [u8; SEED_LEN], addend: &[u8]) {
+    let mut carry = 0u16;
+    let mut addend = addend.iter().rev();
+    for byte in value.iter_mut().rev() {
+        let sum = *byte as u16 + *addend.next().unwrap_or(&0) as u16 + carry;
+        *byte = sum as u8;
+        carry = sum >> 8;
+    }
+}
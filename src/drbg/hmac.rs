@@ -0,0 +1,108 @@
+//! HMAC_DRBG with HMAC-SHA-256, SP 800-90A section 10.1.2.
+
+use super::sha256::{DIGEST_LEN, hmac};
+use super::{Drbg, DrbgError, ReseedCounter, check_inputs, fill_generated};
+use crate::macros::rng_api_methods;
+use crate::pool::wipe;
+use athena::rng_api::{RngApi, RngResult};
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+/// HMAC_DRBG with HMAC-SHA-256.
+///
+/// The state is an HMAC key `K` and a value `V`; output is `V` repeatedly replaced by
+/// `HMAC(K, V)`. Its security rests only on HMAC being a pseudorandom function, which makes it
+/// the most conservative of the three generators.
+pub struct HmacDrbg {
+    k: [u8; DIGEST_LEN],
+    v: [u8; DIGEST_LEN],
+    reseed_counter: ReseedCounter,
+}
+
+impl HmacDrbg {
+    /// HMAC_DRBG_Update, mixing the concatenation of `provided` into `K` and `V`.
+    fn update(&mut self, provided: &[&[u8]]) {
+        let mut parts = vec![&self.v[..], &[0x00]];
+        parts.extend_from_slice(provided);
+        self.k = hmac(&self.k, &parts);
+        self.v = hmac(&self.k, &[&self.v]);
+        if provided.iter().all(|part| part.is_empty()) {
+            return;
+        }
+        let mut parts = vec![&self.v[..], &[0x01]];
+        parts.extend_from_slice(provided);
+        self.k = hmac(&self.k, &parts);
+        self.v = hmac(&self.k, &[&self.v]);
+    }
+
+    fn fill(&mut self, buffer: &mut [u8]) -> RngResult<()> {
+        let forked = self.reseed_counter.forked();
+        fill_generated(self, forked, buffer)
+    }
+}
+
+impl Drbg for HmacDrbg {
+    fn instantiate(
+        entropy_input: &[u8],
+        nonce: &[u8],
+        personalization: &[u8],
+    ) -> Result<Self, DrbgError> {
+        check_inputs(entropy_input, Some(nonce), &[personalization])?;
+        let mut drbg = HmacDrbg {
+            k: [0x00; DIGEST_LEN],
+            v: [0x01; DIGEST_LEN],
+            reseed_counter: ReseedCounter::new(),
+        };
+        drbg.update(&[entropy_input, nonce, personalization]);
+        Ok(drbg)
+    }
+
+    fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        check_inputs(entropy_input, None, &[additional_input])?;
+        self.update(&[entropy_input, additional_input]);
+        self.reseed_counter.reset();
+        Ok(())
+    }
+
+    fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        self.reseed_counter.check(output, additional_input)?;
+        if !additional_input.is_empty() {
+            self.update(&[additional_input]);
+        }
+        for chunk in output.chunks_mut(DIGEST_LEN) {
+            self.v = hmac(&self.k, &[&self.v]);
+            chunk.copy_from_slice(&self.v[..chunk.len()]);
+        }
+        self.update(&[additional_input]);
+        self.reseed_counter.counter += 1;
+        Ok(())
+    }
+
+    fn reseed_counter(&self) -> u64 {
+        self.reseed_counter.counter
+    }
+
+    fn reseed_interval(&self) -> u64 {
+        self.reseed_counter.interval
+    }
+
+    fn set_reseed_interval(&mut self, interval: u64) -> Result<(), DrbgError> {
+        self.reseed_counter.set_interval(interval)
+    }
+}
+
+impl RngApi for HmacDrbg {
+    rng_api_methods!();
+}
+
+impl Debug for HmacDrbg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "HmacDrbg {{ .. }}")
+    }
+}
+
+impl Drop for HmacDrbg {
+    fn drop(&mut self) {
+        wipe(&mut self.k);
+        wipe(&mut self.v);
+    }
+}
@@ -0,0 +1,280 @@
+//! Deterministic random bit generators from NIST SP 800-90A Rev. 1.
+//!
+//! [`HashDrbg`] (Hash_DRBG with SHA-256), [`HmacDrbg`] (HMAC_DRBG with HMAC-SHA-256) and
+//! [`CtrDrbg`] (CTR_DRBG with AES-256 and the derivation function) all provide a security
+//! strength of 256 bits. SHA-256 and AES-256 are implemented in this crate, so no dependency is
+//! added.
+//!
+//! Each generator exposes the four functions of the standard through the [`Drbg`] trait:
+//! [`instantiate`](Drbg::instantiate), [`reseed`](Drbg::reseed), [`generate`](Drbg::generate)
+//! and [`uninstantiate`](Drbg::uninstantiate). [`Drbg::from_entropy`] and
+//! [`Drbg::reseed_from_entropy`] take the entropy input and nonce from the operating system
+//! through [`GetRandom`].
+//!
+//! The generators implement [`RngApi`](athena::rng_api::RngApi) too. Used that way, requests
+//! larger than [`MAX_REQUEST_BYTES`] are split, and the generator reseeds itself from the
+//! operating system when its reseed counter runs out or the process has forked. Calls to
+//! [`Drbg::generate`] are left exactly as the standard specifies, so a forked child must
+//! reseed explicitly before using them.
+//!
+//! ## Example
+//! ```
+//! use athena::rng_api::RngApi;
+//! use tyche::drbg::{Drbg, HmacDrbg};
+//!
+//! let mut drbg = HmacDrbg::from_entropy(b"session keys").unwrap();
+//! let mut key = [0u8; 32];
+//! drbg.generate(&mut key, &[]).unwrap();
+//! let nonce = drbg.random_u64().unwrap();
+//! ```
+
+#[cfg(test)]
+mod tests;
+
+mod aes;
+mod ctr;
+mod hash;
+mod hmac;
+mod sha256;
+
+pub use ctr::CtrDrbg;
+pub use hash::HashDrbg;
+pub use hmac::HmacDrbg;
+
+use crate::entropy::{EntropySource, GetRandom};
+use crate::fork;
+use crate::pool::wipe;
+use athena::rng_api::{RngError, RngResult};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The security strength of every generator in this module, in bytes.
+pub const SECURITY_STRENGTH: usize = 32;
+
+/// The shortest accepted entropy input, in bytes: one byte per byte of security strength.
+pub const MIN_ENTROPY_BYTES: usize = SECURITY_STRENGTH;
+
+/// The shortest accepted nonce, in bytes: half the security strength.
+pub const MIN_NONCE_BYTES: usize = SECURITY_STRENGTH / 2;
+
+/// The longest accepted entropy input, nonce, personalization string or additional input,
+/// `2^35` bits.
+pub const MAX_INPUT_BYTES: u64 = 1 << 32;
+
+/// The most bytes a single [`Drbg::generate`] call may return, `2^19` bits.
+pub const MAX_REQUEST_BYTES: usize = 1 << 16;
+
+/// The most [`Drbg::generate`] calls between two reseeds, and the default reseed interval.
+pub const MAX_RESEED_INTERVAL: u64 = 1 << 48;
+
+/// Why a DRBG function failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrbgError {
+    /// The entropy input is shorter than [`MIN_ENTROPY_BYTES`].
+    EntropyTooShort,
+    /// The nonce is shorter than [`MIN_NONCE_BYTES`].
+    NonceTooShort,
+    /// An input is longer than [`MAX_INPUT_BYTES`].
+    InputTooLong,
+    /// More than [`MAX_REQUEST_BYTES`] were requested at once.
+    RequestTooLarge,
+    /// The reseed counter has passed the reseed interval; call [`Drbg::reseed`] first.
+    ReseedRequired,
+    /// The reseed interval is 0 or larger than [`MAX_RESEED_INTERVAL`].
+    BadReseedInterval,
+}
+
+impl Display for DrbgError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            DrbgError::EntropyTooShort => write!(
+                f,
+                "The entropy input must be at least {MIN_ENTROPY_BYTES} bytes long!"
+            ),
+            DrbgError::NonceTooShort => write!(
+                f,
+                "The nonce must be at least {MIN_NONCE_BYTES} bytes long!"
+            ),
+            DrbgError::InputTooLong => write!(f, "An input must be at most 2^32 bytes long!"),
+            DrbgError::RequestTooLarge => write!(
+                f,
+                "At most {MAX_REQUEST_BYTES} bytes can be generated at once!"
+            ),
+            DrbgError::ReseedRequired => write!(f, "The generator must be reseeded!"),
+            DrbgError::BadReseedInterval => {
+                write!(f, "The reseed interval must be between 1 and 2^48!")
+            }
+        }
+    }
+}
+
+impl Error for DrbgError {}
+
+impl From<DrbgError> for RngError {
+    fn from(e: DrbgError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// A deterministic random bit generator following NIST SP 800-90A.
+///
+/// The internal state is wiped when the generator is dropped.
+pub trait Drbg: Sized {
+    /// Create the generator from `entropy_input`, a `nonce` and an optional `personalization`
+    /// string (pass `&[]` for none), which makes it distinct from other instances.
+    ///
+    /// ## Errors
+    /// Returns `DrbgError` if the entropy input or nonce is too short, or an input too long.
+    fn instantiate(
+        entropy_input: &[u8],
+        nonce: &[u8],
+        personalization: &[u8],
+    ) -> Result<Self, DrbgError>;
+
+    /// Mix fresh `entropy_input` and optional `additional_input` into the state and reset the
+    /// reseed counter.
+    ///
+    /// ## Errors
+    /// Returns `DrbgError` if the entropy input is too short or an input too long.
+    fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) -> Result<(), DrbgError>;
+
+    /// Fill `output` with pseudorandom bytes, mixing in the optional `additional_input` first.
+    ///
+    /// ## Errors
+    /// Returns `DrbgError` if `output` is longer than [`MAX_REQUEST_BYTES`], the additional
+    /// input too long, or a reseed is required.
+    fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), DrbgError>;
+
+    /// The number of the next [`generate`](Drbg::generate) call since the last (re)seed,
+    /// starting at 1.
+    fn reseed_counter(&self) -> u64;
+
+    /// The number of [`generate`](Drbg::generate) calls allowed between two reseeds.
+    fn reseed_interval(&self) -> u64;
+
+    /// Set the number of [`generate`](Drbg::generate) calls allowed between two reseeds.
+    ///
+    /// ## Errors
+    /// Returns `DrbgError` if `interval` is 0 or larger than [`MAX_RESEED_INTERVAL`].
+    fn set_reseed_interval(&mut self, interval: u64) -> Result<(), DrbgError>;
+
+    /// Destroy the generator, wiping its internal state.
+    fn uninstantiate(self) {
+        drop(self);
+    }
+
+    /// Instantiate the generator with entropy input and nonce from the operating system.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the operating system fails to provide entropy, or the
+    /// personalization string is too long.
+    fn from_entropy(personalization: &[u8]) -> RngResult<Self> {
+        let mut seed = [0u8; MIN_ENTROPY_BYTES + MIN_NONCE_BYTES];
+        GetRandom::new()?.fill_bytes(&mut seed)?;
+        let (entropy_input, nonce) = seed.split_at(MIN_ENTROPY_BYTES);
+        let drbg = Self::instantiate(entropy_input, nonce, personalization);
+        wipe(&mut seed);
+        Ok(drbg?)
+    }
+
+    /// Reseed the generator with entropy input from the operating system.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the operating system fails to provide entropy, or the additional
+    /// input is too long.
+    fn reseed_from_entropy(&mut self, additional_input: &[u8]) -> RngResult<()> {
+        let mut entropy_input = [0u8; MIN_ENTROPY_BYTES];
+        GetRandom::new()?.fill_bytes(&mut entropy_input)?;
+        let result = self.reseed(&entropy_input, additional_input);
+        wipe(&mut entropy_input);
+        Ok(result?)
+    }
+}
+
+/// Reject inputs the standard does not allow, shared by every `instantiate` and `reseed`.
+fn check_inputs(
+    entropy_input: &[u8],
+    nonce: Option<&[u8]>,
+    inputs: &[&[u8]],
+) -> Result<(), DrbgError> {
+    if entropy_input.len() < MIN_ENTROPY_BYTES {
+        return Err(DrbgError::EntropyTooShort);
+    }
+    if nonce.is_some_and(|nonce| nonce.len() < MIN_NONCE_BYTES) {
+        return Err(DrbgError::NonceTooShort);
+    }
+    let too_long = |input: &[u8]| input.len() as u64 > MAX_INPUT_BYTES;
+    if too_long(entropy_input) || nonce.is_some_and(too_long) || inputs.iter().any(|i| too_long(i))
+    {
+        return Err(DrbgError::InputTooLong);
+    }
+    Ok(())
+}
+
+/// The reseed counter and interval of a generator.
+#[derive(Clone, Copy, Debug)]
+struct ReseedCounter {
+    counter: u64,
+    interval: u64,
+    /// The fork generation of the last (re)seed.
+    generation: u64,
+}
+
+impl ReseedCounter {
+    fn new() -> Self {
+        ReseedCounter {
+            counter: 1,
+            interval: MAX_RESEED_INTERVAL,
+            generation: fork::generation(),
+        }
+    }
+
+    /// Restart counting after a reseed.
+    fn reset(&mut self) {
+        self.counter = 1;
+        self.generation = fork::generation();
+    }
+
+    /// Whether the process forked since the last (re)seed.
+    fn forked(&self) -> bool {
+        self.generation != fork::generation()
+    }
+
+    /// Check a generate request before any state is touched.
+    fn check(&self, output: &[u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        if output.len() > MAX_REQUEST_BYTES {
+            return Err(DrbgError::RequestTooLarge);
+        }
+        if additional_input.len() as u64 > MAX_INPUT_BYTES {
+            return Err(DrbgError::InputTooLong);
+        }
+        if self.counter > self.interval {
+            return Err(DrbgError::ReseedRequired);
+        }
+        Ok(())
+    }
+
+    fn set_interval(&mut self, interval: u64) -> Result<(), DrbgError> {
+        if interval == 0 || interval > MAX_RESEED_INTERVAL {
+            return Err(DrbgError::BadReseedInterval);
+        }
+        self.interval = interval;
+        Ok(())
+    }
+}
+
+/// Fill `buffer` for the `RngApi` impls: split it into requests the standard allows and
+/// reseed from the operating system whenever the reseed counter runs out, or first if the
+/// process `forked` since the last seed.
+fn fill_generated<D: Drbg>(drbg: &mut D, forked: bool, buffer: &mut [u8]) -> RngResult<()> {
+    if forked {
+        drbg.reseed_from_entropy(&[])?;
+    }
+    for chunk in buffer.chunks_mut(MAX_REQUEST_BYTES) {
+        if drbg.reseed_counter() > drbg.reseed_interval() {
+            drbg.reseed_from_entropy(&[])?;
+        }
+        drbg.generate(chunk, &[])?;
+    }
+    Ok(())
+}
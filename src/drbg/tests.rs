@@ -0,0 +1,377 @@
+use super::aes::Aes256;
+use super::sha256::{Sha256, hmac};
+use super::*;
+use athena::rng_api::RngApi;
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// `len` bytes counting up from `start`, the inputs of the vectors not taken from CAVP.
+fn counting(start: u8, len: u8) -> Vec<u8> {
+    (start..start + len).collect()
+}
+
+/// Run a CAVP style test: instantiate, optionally reseed, generate twice and return the
+/// output of the second call.
+fn cavp<D: Drbg>(
+    entropy_input: &[u8],
+    nonce: &[u8],
+    personalization: &[u8],
+    reseed: Option<(&[u8], &[u8])>,
+    additional: [&[u8]; 2],
+    len: usize,
+) -> Vec<u8> {
+    let mut drbg = D::instantiate(entropy_input, nonce, personalization).unwrap();
+    if let Some((entropy_input, additional_input)) = reseed {
+        drbg.reseed(entropy_input, additional_input).unwrap();
+    }
+    let mut output = vec![0; len];
+    drbg.generate(&mut output, additional[0]).unwrap();
+    drbg.generate(&mut output, additional[1]).unwrap();
+    output
+}
+
+/// The vectors not taken from CAVP use counting inputs: entropy 00.., nonce 20.., personalization
+/// 40.., additional inputs 60.. and a0.., reseed entropy 80.. and reseed additional input c0...
+/// Their outputs were cross-checked against OpenSSL's EVP_RAND implementations.
+fn counting_vectors<D: Drbg>(plain: &str, personalized: &str, reseeded: &str) {
+    let entropy_input = counting(0x00, 32);
+    let nonce = counting(0x20, 16);
+    let personalization = counting(0x40, 32);
+    let additional = [counting(0x60, 32), counting(0xa0, 32)];
+    let reseed = (counting(0x80, 32), counting(0xc0, 32));
+    let additional = [&additional[0][..], &additional[1][..]];
+
+    let output = cavp::<D>(&entropy_input, &nonce, &[], None, [&[], &[]], 128);
+    assert_eq!(output, hex(plain));
+    let output = cavp::<D>(
+        &entropy_input,
+        &nonce,
+        &personalization,
+        None,
+        additional,
+        128,
+    );
+    assert_eq!(output, hex(personalized));
+    let output = cavp::<D>(
+        &entropy_input,
+        &nonce,
+        &personalization,
+        Some((&reseed.0, &reseed.1)),
+        additional,
+        128,
+    );
+    assert_eq!(output, hex(reseeded));
+}
+
+#[test]
+fn test_sha256_fips_180_vectors() {
+    assert_eq!(
+        Sha256::digest(&[b"abc"]).to_vec(),
+        hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+    assert_eq!(
+        Sha256::digest(&[]).to_vec(),
+        hex("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+    );
+    assert_eq!(
+        Sha256::digest(&[b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"]).to_vec(),
+        hex("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1")
+    );
+    // One million 'a', fed in uneven pieces to exercise the buffering
+    let mut hasher = Sha256::new();
+    let a = [b'a'; 1000];
+    for i in 0..1000 {
+        hasher.update(&a[..i % 7 * 100]);
+        hasher.update(&a[i % 7 * 100..]);
+    }
+    assert_eq!(
+        hasher.finish().to_vec(),
+        hex("cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0")
+    );
+}
+
+#[test]
+fn test_hmac_sha256_rfc4231_vectors() {
+    assert_eq!(
+        hmac(&[0x0b; 20], &[b"Hi There"]).to_vec(),
+        hex("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7")
+    );
+    // A key longer than the block is hashed first
+    assert_eq!(
+        hmac(
+            &[0xaa; 131],
+            &[
+                b"Test Using Larger Than ",
+                b"Block-Size Key - Hash Key First"
+            ]
+        )
+        .to_vec(),
+        hex("60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54")
+    );
+}
+
+#[test]
+fn test_aes256_fips_197_vector() {
+    let key = counting(0x00, 32).try_into().unwrap();
+    let mut block = hex("00112233445566778899aabbccddeeff").try_into().unwrap();
+    Aes256::new(&key).encrypt(&mut block);
+    assert_eq!(block.to_vec(), hex("8ea2b7ca516745bfeafc49904b496089"));
+}
+
+#[test]
+fn test_hash_drbg_cavp_vector() {
+    // CAVP Hash_DRBG.rsp, [SHA-256] no reseed, no prediction resistance, COUNT = 0
+    let output = cavp::<HashDrbg>(
+        &hex("a65ad0f345db4e0effe875c3a2e71f42c7129d620ff5c119a9ef55f05185e0fb"),
+        &hex("8581f9317517276e06e9607ddbcbcc2e"),
+        &[],
+        None,
+        [&[], &[]],
+        128,
+    );
+    assert_eq!(
+        output,
+        hex(
+            "d3e160c35b99f340b2628264d1751060e0045da383ff57a57d73a673d2b8d80d\
+             aaf6a6c35a91bb4579d73fd0c8fed111b0391306828adfed528f018121b3febd\
+             c343e797b87dbb63db1333ded9d1ece177cfa6b71fe8ab1da46624ed6415e51c\
+             cde2c7ca86e283990eeaeb91120415528b2295910281b02dd431f4c9f70427df"
+        )
+    );
+}
+
+#[test]
+fn test_hash_drbg_counting_vectors() {
+    counting_vectors::<HashDrbg>(
+        "27a3342a35d4bbb8e1dcd8ec0fc1a0d1a25cf906f0445d3b974dbddf4a3ba34e\
+         073302ab655234a703381741af7b15191a96164cc087ad1ef8360960b94dfba7\
+         451ade5f57ff6f74afeb737f8f539304c1ce58a98f3ad4b852b4cec0aceffb2b\
+         d5f153f9395b593dc8d890c6d9cc570107b36cfd4b7081c42102efd89752a1de",
+        "0d0c4c062b1c8f489b68c6111c3b5b790d67f1250f9cca6bed1e981d8e0d253e\
+         5ccbc1674cc7578a488e61cca82221b7315007aa3c649595f98a7269e5eff921\
+         71033218ddff8e3d476302b49bd5fb896d87d7be3e5662f328db5f023c88268f\
+         732e4ede898a585fb1211e114e0fee0ca4226b6e4c5f49e6163348c0d5b69375",
+        "67f2ab1f9a74c26d4033eef91a826686de692ff8dba7d3cac364549a38827f87\
+         bb140f98ed286eaecbc6b684a4af80e12644ec7c8013c2727e8a72f81f26bfad\
+         9a4ec07261f9b430292c093df0822a7e0b4696ceca53975006659197ad1b5b77\
+         63962d348d19bde9958530da3f47914f9044d446a1801e4b6f8d47ff012ba9ff",
+    );
+}
+
+#[test]
+fn test_hmac_drbg_cavp_vectors() {
+    // CAVP HMAC_DRBG.rsp, [SHA-256] no reseed, no prediction resistance, COUNT = 0 and
+    // COUNT = 0 and 1 of the variant with additional input
+    let output = cavp::<HmacDrbg>(
+        &hex("ca851911349384bffe89de1cbdc46e6831e44d34a4fb935ee285dd14b71a7488"),
+        &hex("659ba96c601dc69fc902940805ec0ca8"),
+        &[],
+        None,
+        [&[], &[]],
+        128,
+    );
+    assert_eq!(
+        output,
+        hex(
+            "e528e9abf2dece54d47c7e75e5fe302149f817ea9fb4bee6f4199697d04d5b89\
+             d54fbb978a15b5c443c9ec21036d2460b6f73ebad0dc2aba6e624abf07745bc1\
+             07694bb7547bb0995f70de25d6b29e2d3011bb19d27676c07162c8b5ccde0668\
+             961df86803482cb37ed6d5c0bb8d50cf1f50d476aa0458bdaba806f48be9dcb8"
+        )
+    );
+
+    let output = cavp::<HmacDrbg>(
+        &hex("d3cc4d1acf3dde0c4bd2290d262337042dc632948223d3a2eaab87da44295fbd"),
+        &hex("0109b0e729f457328aa18569a9224921"),
+        &[],
+        None,
+        [
+            &hex("3c311848183c9a212a26f27f8c6647e40375e466a0857cc39c4e47575d53f1f6"),
+            &hex("fcb9abd19ccfbccef88c9c39bfb3dd7b1c12266c9808992e305bc3cff566e4e4"),
+        ],
+        128,
+    );
+    assert_eq!(
+        output,
+        hex(
+            "9c7b758b212cd0fcecd5daa489821712e3cdea4467b560ef5ddc24ab47749a1f\
+             1ffdbbb118f4e62fcfca3371b8fbfc5b0646b83e06bfbbab5fac30ea09ea2bc7\
+             6f1ea568c9be0444b2cc90517b20ca825f2d0eccd88e7175538b85d90ab39018\
+             3ca6395535d34473af6b5a5b88f5a59ee7561573337ea819da0dcc3573a22974"
+        )
+    );
+
+    let output = cavp::<HmacDrbg>(
+        &hex("f97a3cfd91faa046b9e61b9493d436c4931f604b22f1081521b3419151e8ff06"),
+        &hex("11f3a7d43595357d58120bd1e2dd8aed"),
+        &[],
+        None,
+        [
+            &hex("517289afe444a0fe5ed1a41dbbb5eb17150079bdd31e29cf2ff30034d8268e3b"),
+            &hex("88028d29ef80b4e6f0fe12f91d7449fe75062682e89c571440c0c9b52c42a6e0"),
+        ],
+        128,
+    );
+    assert_eq!(
+        output,
+        hex(
+            "c6871cff0824fe55ea7689a52229886730450e5d362da5bf590dcf9acd67fed4\
+             cb32107df5d03969a66b1f6494fdf5d63d5b4d0d34ea7399a07d0116126d0d51\
+             8c7c55ba46e12f62efc8fe28a51c9d428e6d371d7397ab319fc73ded4722e5b4\
+             f30004032a6128df5e7497ecf82ca7b0a50e867ef6728a4f509a8c859087039c"
+        )
+    );
+}
+
+#[test]
+fn test_hmac_drbg_counting_vectors() {
+    counting_vectors::<HmacDrbg>(
+        "f3f5ea84d3a45fa2dee0071c508d64f6d0de295777226be6a3d5ed5b0301c7bc\
+         22a223ab52c6c712357c7ba25829445ae26da7e4ec99715fe62e41fdd8737d8c\
+         970eb25fb50942a63d472e913699a369fc5923bc73c1e2fb8bb15090df398cbb\
+         1f73b2e0ea42233580c1ba1f19339e0b66934e46bb09d5865d668a4a52f0b3ec",
+        "2146fc80df5e46a9582a5ea05bacc6040e15454bca0809ccf186ca3acbb84c98\
+         0f980453491e9f01f3ae7e178255ce99e0e368436a5609818313639894647673\
+         db55bb514f87e5f72558012011ce0651d28f2bb67fe82eaeae7b49cbcf50f62e\
+         87bb0e0116771b9f991ee87f8a343a57e195519e1fb88172d688f0bec358f892",
+        "ea1e9731285d92d79629f5136218a7cf49889bfc014f04e346c3cb292225ae42\
+         7d1ede8c769ce7ce6071c86fa7500201b269b3c5edfd4eeaa5ae5df45334bad3\
+         fd6070abd9855131541ac3be8900826862139e0a123a402beeb4b35c9ca7e4d5\
+         84c913a937983d56acb9861e7ee92c768298044553d6677d78a5a524ba347097",
+    );
+}
+
+#[test]
+fn test_ctr_drbg_cavp_vector() {
+    // CAVP CTR_DRBG.rsp, [AES-256 use df] no reseed, no prediction resistance, COUNT = 0
+    let output = cavp::<CtrDrbg>(
+        &hex("36401940fa8b1fba91a1661f211d78a0b9389a74e5bccfece8d766af1a6d3b14"),
+        &hex("496f25b0f1301b4f501be30380a137eb"),
+        &[],
+        None,
+        [&[], &[]],
+        64,
+    );
+    assert_eq!(
+        output,
+        hex(
+            "5862eb38bd558dd978a696e6df164782ddd887e7e9a6c9f3f1fbafb78941b535\
+             a64912dfd224c6dc7454e5250b3d97165e16260c2faf1cc7735cb75fb4f07e1d"
+        )
+    );
+}
+
+#[test]
+fn test_ctr_drbg_counting_vectors() {
+    counting_vectors::<CtrDrbg>(
+        "ae56ecb53ae4cc2591c37e0693caa57a64778b053bbbc5f75ad1a353aefe7bb9\
+         fe9a7777099c85d6372930c3f474a60b177ba3ef59c23b7d1675409ca04e1fd3\
+         1db27c7787826c4f65f58b31afc9187221cf528709e49b1a97a08d84bf741da2\
+         836ccf578764cf95a348e40cb9703288c88d01b87c81da6d70f8855af9eb61bc",
+        "79c8ce457a707a12d37ad4ca2ff0a4572a543fcc18f82f49e3f95b8d4e964c33\
+         d1d3e8092a726e5263c553a08d72a98a66602a792232509922fda72fdb767ec9\
+         1843fb4fb86e12410016c1dbd0e88bc6fee6d4482ccded7ba3c56b4df2eaec42\
+         1518b196c4eb8cec485ab3072bb49c3e0c5a3d00732c479eae9fc125fd8e3165",
+        "e8c10830cabef3b9c4fe2b57eec4e9c1de8143aca2035f0acbcf5f604437b445\
+         15599b8d020a5759b24ed9b388d74fec5210f6a8eea16f5574108917774c8537\
+         77cdb21dff5d80643efecfde0828793bb439c45d628beeed552c02b3ff3a0b04\
+         f0bd32c7f8c14e221a837b65409306ae032a003cbf817d09670f78fd1a7fdedb",
+    );
+}
+
+fn check_errors<D: Drbg>() {
+    let entropy_input = [7u8; 32];
+    let nonce = [9u8; 16];
+    assert_eq!(
+        D::instantiate(&entropy_input[..31], &nonce, &[]).err(),
+        Some(DrbgError::EntropyTooShort)
+    );
+    assert_eq!(
+        D::instantiate(&entropy_input, &nonce[..15], &[]).err(),
+        Some(DrbgError::NonceTooShort)
+    );
+
+    let mut drbg = D::instantiate(&entropy_input, &nonce, b"errors").unwrap();
+    let mut output = vec![0; MAX_REQUEST_BYTES + 1];
+    assert_eq!(
+        drbg.generate(&mut output, &[]),
+        Err(DrbgError::RequestTooLarge)
+    );
+    assert_eq!(
+        drbg.reseed(&entropy_input[..16], &[]),
+        Err(DrbgError::EntropyTooShort)
+    );
+    assert_eq!(drbg.reseed_counter(), 1);
+    assert_eq!(drbg.reseed_interval(), MAX_RESEED_INTERVAL);
+    assert_eq!(
+        drbg.set_reseed_interval(0),
+        Err(DrbgError::BadReseedInterval)
+    );
+    assert_eq!(
+        drbg.set_reseed_interval(MAX_RESEED_INTERVAL + 1),
+        Err(DrbgError::BadReseedInterval)
+    );
+
+    drbg.set_reseed_interval(2).unwrap();
+    drbg.generate(&mut output[..32], &[]).unwrap();
+    drbg.generate(&mut output[..32], &[]).unwrap();
+    assert_eq!(drbg.reseed_counter(), 3);
+    assert_eq!(
+        drbg.generate(&mut output[..32], &[]),
+        Err(DrbgError::ReseedRequired)
+    );
+    drbg.reseed(&entropy_input, b"more").unwrap();
+    assert_eq!(drbg.reseed_counter(), 1);
+    drbg.generate(&mut output[..32], &[]).unwrap();
+    drbg.uninstantiate();
+}
+
+#[test]
+fn test_drbg_errors() {
+    check_errors::<HashDrbg>();
+    check_errors::<HmacDrbg>();
+    check_errors::<CtrDrbg>();
+    assert_eq!(
+        DrbgError::ReseedRequired.to_string(),
+        "The generator must be reseeded!"
+    );
+}
+
+fn check_rng_api<D: Drbg + RngApi>() {
+    let mut a = D::from_entropy(b"tyche tests").unwrap();
+    let mut b = D::from_entropy(b"tyche tests").unwrap();
+    assert_ne!(a.random_u64().unwrap(), b.random_u64().unwrap());
+
+    // Requests above the limit are split, and the reseed counter never blocks the RngApi
+    a.set_reseed_interval(1).unwrap();
+    let bytes = a.random_bytes(3 * MAX_REQUEST_BYTES + 5).unwrap();
+    assert_eq!(bytes.len(), 3 * MAX_REQUEST_BYTES + 5);
+    assert_eq!(a.reseed_counter(), 2);
+    let mut counts = [0u32; 256];
+    for byte in bytes {
+        counts[byte as usize] += 1;
+    }
+    let expected = (3 * MAX_REQUEST_BYTES + 5) as f64 / 256.0;
+    let stat = counts
+        .iter()
+        .map(|&c| (c as f64 - expected).powi(2) / expected)
+        .sum::<f64>();
+    assert!(stat < 377.08, "chi-square {stat}");
+
+    a.reseed_from_entropy(b"fresh").unwrap();
+    assert_eq!(a.reseed_counter(), 1);
+}
+
+#[test]
+fn test_drbg_rng_api() {
+    check_rng_api::<HashDrbg>();
+    check_rng_api::<HmacDrbg>();
+    check_rng_api::<CtrDrbg>();
+    let drbg = HmacDrbg::from_entropy(&[]).unwrap();
+    assert_eq!(format!("{drbg:?}"), "HmacDrbg { .. }");
+}
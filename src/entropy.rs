@@ -0,0 +1,117 @@
+//! Entropy sources that can back a [`Tyche`](crate::Tyche) instance.
+//!
+//! Tyche ships three sources:
+//!
+//! - [`GetRandom`], the default: the `getrandom(2)` syscall, falling back to `/dev/urandom` on `ENOSYS`.
+//! - [`Urandom`]: always reads `/dev/urandom` through a file descriptor.
+//! - [`ReaderSource`]: wraps any `std::io::Read`, for vendored devices or deterministic test input.
+//!
+//! Anything else can be plugged in by implementing [`EntropySource`].
+
+use crate::os;
+use std::fs::File;
+use std::io::{Error as IoError, Read};
+
+/// A source of raw entropy bytes.
+///
+/// Implementors fill the whole buffer or return an error; partial fills are not reported.
+pub trait EntropySource {
+    /// Fill `buffer` completely with entropy.
+    ///
+    /// ## Errors
+    /// Returns `IoError` if the source could not supply `buffer.len()` bytes.
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), IoError>;
+}
+
+impl<S: EntropySource + ?Sized> EntropySource for &mut S {
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), IoError> {
+        (**self).fill_bytes(buffer)
+    }
+}
+
+impl<S: EntropySource + ?Sized> EntropySource for Box<S> {
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), IoError> {
+        (**self).fill_bytes(buffer)
+    }
+}
+
+/// Reads entropy from `/dev/urandom`.
+pub struct Urandom(File);
+
+impl Urandom {
+    /// Open `/dev/urandom`.
+    ///
+    /// ## Errors
+    /// Returns `IoError` if `/dev/urandom` cannot be opened.
+    pub fn new() -> Result<Self, IoError> {
+        Ok(Urandom(File::open("/dev/urandom")?))
+    }
+}
+
+impl EntropySource for Urandom {
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), IoError> {
+        self.0.read_exact(buffer)
+    }
+}
+
+/// Reads entropy through the `getrandom(2)` syscall, without holding a file descriptor.
+///
+/// If the kernel does not know the syscall (`ENOSYS`), `/dev/urandom` is opened and used instead.
+pub struct GetRandom {
+    fallback: Option<Urandom>,
+}
+
+impl GetRandom {
+    /// Create a new `getrandom(2)` source.
+    ///
+    /// ## Errors
+    /// Returns `IoError` if `getrandom(2)` is unavailable and `/dev/urandom` cannot be opened.
+    pub fn new() -> Result<Self, IoError> {
+        if os::getrandom_available() {
+            Ok(GetRandom { fallback: None })
+        } else {
+            Ok(GetRandom {
+                fallback: Some(Urandom::new()?),
+            })
+        }
+    }
+}
+
+impl EntropySource for GetRandom {
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), IoError> {
+        if let Some(urandom) = &mut self.fallback {
+            return urandom.fill_bytes(buffer);
+        }
+        match os::getrandom_fill(buffer) {
+            // The syscall can still be filtered away (e.g. by seccomp) after probing.
+            Err(e) if e.raw_os_error() == Some(os::ENOSYS) => {
+                self.fallback = Some(Urandom::new()?);
+                self.fill_bytes(buffer)
+            }
+            other => other,
+        }
+    }
+}
+
+/// Uses any `std::io::Read` as an entropy source.
+///
+/// Tyche trusts the reader: it is only as unpredictable as the bytes it yields.
+pub struct ReaderSource<R: Read>(R);
+
+impl<R: Read> ReaderSource<R> {
+    /// Wrap `reader` as an entropy source.
+    pub fn new(reader: R) -> Self {
+        ReaderSource(reader)
+    }
+
+    /// Return the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.0
+    }
+}
+
+impl<R: Read> EntropySource for ReaderSource<R> {
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), IoError> {
+        self.0.read_exact(buffer)
+    }
+}
@@ -80,6 +80,12 @@ fn example_random_u64() {
     println!("Generated random u64: {}", random_number);
 }
 
+#[test]
+fn example_random_u128() {
+    let random_number: u128 = random_u128().unwrap();
+    println!("Generated random u128: {}", random_number);
+}
+
 #[test]
 fn example_random_i8() {
     let random_number: i8 = random_i8().unwrap();
@@ -140,6 +146,13 @@ fn example_random_from_i32range() {
     println!("Chosen element {chosen_element}, in range -100, 100");
 }
 
+#[test]
+fn example_gen_range() {
+    let die: u8 = gen_range(1..=6).unwrap();
+    let temperature: f32 = gen_range(-10.0..35.0).unwrap();
+    println!("Rolled a {die}, it is {temperature} degrees");
+}
+
 #[test]
 fn example_random_index() {
     let collection = (0..100).collect::<Vec<usize>>();
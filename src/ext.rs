@@ -0,0 +1,463 @@
+//! Generators available on every [`RngApi`] implementation.
+
+use crate::distributions::{Bernoulli, Distribution};
+use crate::index::{self, IndexVec};
+use crate::uniform::{self, SampleUniform};
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::ops::RangeBounds;
+
+/// Extra generators for every [`RngApi`] implementation, including [`Tyche`](crate::Tyche).
+///
+/// Implemented automatically, bring it into scope with `use tyche::RngApiExt;`.
+pub trait RngApiExt: RngApi {
+    /// Generate a uniformly distributed `f32` in `[0, 1)`.
+    ///
+    /// Uses 24 random bits, so every multiple of `2^-24` in the interval is equally likely.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn random_unit_f32(&mut self) -> RngResult<f32> {
+        Ok((self.random_u32()? >> 8) as f32 * (1.0 / (1u32 << 24) as f32))
+    }
+
+    /// Generate a uniformly distributed `f64` in `[0, 1)`.
+    ///
+    /// Uses 53 random bits, so every multiple of `2^-53` in the interval is equally likely.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn random_unit_f64(&mut self) -> RngResult<f64> {
+        Ok((self.random_u64()? >> 11) as f64 * (1.0 / (1u64 << 53) as f64))
+    }
+
+    /// Generate a uniformly distributed `f32` in `(0, 1]`.
+    ///
+    /// Useful as input to `ln()`, which must not be fed 0.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn random_unit_f32_open_closed(&mut self) -> RngResult<f32> {
+        Ok(((self.random_u32()? >> 8) + 1) as f32 * (1.0 / (1u32 << 24) as f32))
+    }
+
+    /// Generate a uniformly distributed `f64` in `(0, 1]`.
+    ///
+    /// Useful as input to `ln()`, which must not be fed 0.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn random_unit_f64_open_closed(&mut self) -> RngResult<f64> {
+        Ok(((self.random_u64()? >> 11) + 1) as f64 * (1.0 / (1u64 << 53) as f64))
+    }
+
+    /// Generate a uniformly distributed `f32` in `[0, 1]`.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn random_unit_f32_closed(&mut self) -> RngResult<f32> {
+        Ok((self.random_u32()? >> 8) as f32 / ((1u32 << 24) - 1) as f32)
+    }
+
+    /// Generate a uniformly distributed `f64` in `[0, 1]`.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn random_unit_f64_closed(&mut self) -> RngResult<f64> {
+        Ok((self.random_u64()? >> 11) as f64 / ((1u64 << 53) - 1) as f64)
+    }
+
+    /// Generate a `bool` that is `true` with probability `p`.
+    ///
+    /// Exact for every `f64` value of `p`, see [`Bernoulli`]. Usually consumes one `u64`.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `p` is NaN or outside `[0, 1]`, or the generator fails.
+    fn random_bool_with_probability(&mut self, p: f64) -> RngResult<bool> {
+        Bernoulli::new(p)?.sample(self)
+    }
+
+    /// Generate a random `i32` in `[min, max]`.
+    ///
+    /// Works over the full domain, `i32::MIN..=i32::MAX` included.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `min` is larger than `max` or the generator fails.
+    fn random_from_i32_range_inclusive(&mut self, min: i32, max: i32) -> RngResult<i32> {
+        if min <= max {
+            let range_size = max.wrapping_sub(min) as u32;
+            Ok(min.wrapping_add(uniform::u32_up_to(self, range_size)? as i32))
+        } else {
+            Err(RngError::Generic(format!(
+                "Min '{min}' is larger than max '{max}'!"
+            )))
+        }
+    }
+
+    /// Generate a random `i64` in `[min, max]`.
+    ///
+    /// Works over the full domain, `i64::MIN..=i64::MAX` included.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `min` is larger than `max` or the generator fails.
+    fn random_from_i64_range_inclusive(&mut self, min: i64, max: i64) -> RngResult<i64> {
+        if min <= max {
+            let range_size = max.wrapping_sub(min) as u64;
+            Ok(min.wrapping_add(uniform::u64_up_to(self, range_size)? as i64))
+        } else {
+            Err(RngError::Generic(format!(
+                "Min '{min}' is larger than max '{max}'!"
+            )))
+        }
+    }
+
+    /// Generate a random `isize` in `[min, max]`.
+    ///
+    /// Works over the full domain, `isize::MIN..=isize::MAX` included.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `min` is larger than `max` or the generator fails.
+    fn random_from_i_range_inclusive(&mut self, min: isize, max: isize) -> RngResult<isize> {
+        if min <= max {
+            let range_size = max.wrapping_sub(min) as usize;
+            Ok(min.wrapping_add(uniform::usize_up_to(self, range_size)? as isize))
+        } else {
+            Err(RngError::Generic(format!(
+                "Min '{min}' is larger than max '{max}'!"
+            )))
+        }
+    }
+
+    /// Generate a random `u128`.
+    ///
+    /// Two `u64` are combined in little-endian order, the first one forming the low half,
+    /// matching how `random_u64` composes its bytes.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn random_u128(&mut self) -> RngResult<u128> {
+        let low = self.random_u64()?;
+        let high = self.random_u64()?;
+        Ok(u128::from(high) << 64 | u128::from(low))
+    }
+
+    /// Generate a random `i128`.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn random_i128(&mut self) -> RngResult<i128> {
+        Ok(self.random_u128()? as i128)
+    }
+
+    /// Generate a random `u128` in `[min, max)`. Returns `min` if both are equal.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `min` is larger than `max` or the generator fails.
+    fn random_from_u128_range(&mut self, min: u128, max: u128) -> RngResult<u128> {
+        if min < max {
+            Ok(min + uniform::u128_below(self, max - min)?)
+        } else if min == max {
+            Ok(min)
+        } else {
+            Err(RngError::Generic(format!(
+                "Min '{min}' is larger than max '{max}'!"
+            )))
+        }
+    }
+
+    /// Generate a random `u128` in `[min, max]`.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `min` is larger than `max` or the generator fails.
+    fn random_from_u128_range_inclusive(&mut self, min: u128, max: u128) -> RngResult<u128> {
+        if min <= max {
+            Ok(min + uniform::u128_up_to(self, max - min)?)
+        } else {
+            Err(RngError::Generic(format!(
+                "Min '{min}' is larger than max '{max}'!"
+            )))
+        }
+    }
+
+    /// Generate a random `i128` in `[min, max)`. Returns `min` if both are equal.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `min` is larger than `max` or the generator fails.
+    fn random_from_i128_range(&mut self, min: i128, max: i128) -> RngResult<i128> {
+        if min < max {
+            let range_size = max.wrapping_sub(min) as u128;
+            Ok(min.wrapping_add(uniform::u128_below(self, range_size)? as i128))
+        } else if min == max {
+            Ok(min)
+        } else {
+            Err(RngError::Generic(format!(
+                "Min '{min}' is larger than max '{max}'!"
+            )))
+        }
+    }
+
+    /// Generate a random `i128` in `[min, max]`.
+    ///
+    /// Works over the full domain, `i128::MIN..=i128::MAX` included.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `min` is larger than `max` or the generator fails.
+    fn random_from_i128_range_inclusive(&mut self, min: i128, max: i128) -> RngResult<i128> {
+        if min <= max {
+            let range_size = max.wrapping_sub(min) as u128;
+            Ok(min.wrapping_add(uniform::u128_up_to(self, range_size)? as i128))
+        } else {
+            Err(RngError::Generic(format!(
+                "Min '{min}' is larger than max '{max}'!"
+            )))
+        }
+    }
+
+    /// Generate a random `u128` in `[0, max]`.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn random_u128_with_ceiling(&mut self, max: u128) -> RngResult<u128> {
+        uniform::u128_up_to(self, max)
+    }
+
+    /// Generate a random value uniformly distributed over `range`.
+    ///
+    /// Works for every primitive integer and float type, and the range syntax states the bounds:
+    ///
+    /// - `a..b` samples from `[a, b)`
+    /// - `a..=b` samples from `[a, b]`
+    /// - `..b` and `..=b` start at the smallest value of the type
+    /// - `a..` ends at the largest value of the type, inclusive
+    ///
+    /// Integer ranges are unbiased over the full domain of the type.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the range is empty, a float bound is not finite or the generator fails.
+    ///
+    /// ## Example
+    /// ```
+    /// use tyche::{RngApiExt, Tyche};
+    ///
+    /// let mut tyche = Tyche::new().unwrap();
+    /// let die: u8 = tyche.gen_range(1..=6).unwrap();
+    /// let offset: i64 = tyche.gen_range(-100..100).unwrap();
+    /// let unit: f64 = tyche.gen_range(0.0..1.0).unwrap();
+    /// assert!((1..=6).contains(&die) && (-100..100).contains(&offset) && unit < 1.0);
+    /// assert!(tyche.gen_range(5..5).is_err());
+    /// ```
+    fn gen_range<T: SampleUniform, B: RangeBounds<T>>(&mut self, range: B) -> RngResult<T> {
+        T::sample_bounds(self, range.start_bound(), range.end_bound())
+    }
+
+    /// Shuffle `slice` in place, every permutation being equally likely.
+    ///
+    /// Uses the Fisher–Yates algorithm: each position, from the last to the second, is swapped
+    /// with a uniformly chosen position at or before it.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails; `slice` may be partially shuffled then.
+    fn shuffle<T>(&mut self, slice: &mut [T]) -> RngResult<()> {
+        for i in (1..slice.len()).rev() {
+            let j = uniform::usize_up_to(self, i)?;
+            slice.swap(i, j);
+        }
+        Ok(())
+    }
+
+    /// Move `amount` uniformly chosen elements of `slice`, in random order, to its front.
+    ///
+    /// This is a Fisher–Yates shuffle that stops after `amount` steps, so it costs `O(amount)`
+    /// rather than `O(slice.len())`. Returns the shuffled front and the untouched remainder.
+    /// `amount` is capped at `slice.len()`.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn partial_shuffle<'a, T>(
+        &mut self,
+        slice: &'a mut [T],
+        amount: usize,
+    ) -> RngResult<(&'a mut [T], &'a mut [T])> {
+        let amount = amount.min(slice.len());
+        for i in 0..amount {
+            let j = i + uniform::usize_below(self, slice.len() - i)?;
+            slice.swap(i, j);
+        }
+        Ok(slice.split_at_mut(amount))
+    }
+
+    /// Choose a uniformly random element of `slice`.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `slice` is empty or the generator fails.
+    fn choose<'a, T>(&mut self, slice: &'a [T]) -> RngResult<&'a T> {
+        let index = self.random_index(slice.len())?;
+        Ok(&slice[index])
+    }
+
+    /// Choose a uniformly random element of `slice`, mutably.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `slice` is empty or the generator fails.
+    fn choose_mut<'a, T>(&mut self, slice: &'a mut [T]) -> RngResult<&'a mut T> {
+        let index = self.random_index(slice.len())?;
+        Ok(&mut slice[index])
+    }
+
+    /// Choose `amount` distinct elements of `slice`, without replacement, in random order.
+    ///
+    /// Every selection and every order is equally likely.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `amount` is larger than `slice.len()` or the generator fails.
+    fn choose_multiple<'a, T>(&mut self, slice: &'a [T], amount: usize) -> RngResult<Vec<&'a T>> {
+        if amount > slice.len() {
+            return Err(RngError::Generic(format!(
+                "Cannot choose {amount} distinct elements out of {}!",
+                slice.len()
+            )));
+        }
+        let indices = self.sample_indices(slice.len(), amount)?;
+        Ok(indices.into_iter().map(|i| &slice[i]).collect())
+    }
+
+    /// Choose an element of `slice` with probability proportional to its weight.
+    ///
+    /// `weight` is called once per element. Weights must be finite and non-negative, and at least
+    /// one must be positive; elements with weight 0 are never chosen.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `slice` is empty, a weight is invalid, all weights are 0 or the generator fails.
+    fn choose_weighted<'a, T, F>(&mut self, slice: &'a [T], mut weight: F) -> RngResult<&'a T>
+    where
+        F: FnMut(&T) -> f64,
+    {
+        if slice.is_empty() {
+            return Err(RngError::Generic(
+                "Cannot choose from an empty collection!".to_string(),
+            ));
+        }
+        let mut cumulative = Vec::with_capacity(slice.len());
+        let mut total = 0.0;
+        for (index, element) in slice.iter().enumerate() {
+            let w = weight(element);
+            if !(w.is_finite() && w >= 0.0) {
+                return Err(RngError::Generic(format!(
+                    "Weight '{w}' of element {index} is not a finite, non-negative number!"
+                )));
+            }
+            total += w;
+            cumulative.push(total);
+        }
+        if total == 0.0 || !total.is_finite() {
+            return Err(RngError::Generic(format!(
+                "The total weight '{total}' must be positive and finite!"
+            )));
+        }
+        let target = total * self.random_unit_f64()?;
+        // The first element whose cumulative weight exceeds the target; elements of weight 0
+        // share the cumulative weight of their predecessor and are skipped.
+        let index = cumulative.partition_point(|&c| c <= target);
+        // Rounding can push the target to the total, fall back to the last positive weight
+        let index = index.min(cumulative.partition_point(|&c| c < total));
+        Ok(&slice[index])
+    }
+
+    /// Choose a uniformly random item of `iter` in a single pass.
+    ///
+    /// Iterators of known length jump straight to the chosen item, others are read to the end,
+    /// keeping the `n`-th item with probability `1 / n` (reservoir sampling of one item).
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `iter` is empty or the generator fails.
+    fn choose_from_iter<I: IntoIterator>(&mut self, iter: I) -> RngResult<I::Item> {
+        let mut iter = iter.into_iter();
+        let empty = || RngError::Generic("Cannot choose from an empty iterator!".to_string());
+        if let (lower, Some(upper)) = iter.size_hint()
+            && lower == upper
+        {
+            let index = self.random_index(lower).map_err(|_| empty())?;
+            return iter.nth(index).ok_or_else(empty);
+        }
+        let mut chosen = iter.next().ok_or_else(empty)?;
+        let mut seen = 1u64;
+        for item in iter {
+            seen += 1;
+            if uniform::u64_below(self, seen)? == 0 {
+                chosen = item;
+            }
+        }
+        Ok(chosen)
+    }
+
+    /// Choose `amount` distinct items of `iter` in a single pass with `O(amount)` memory.
+    ///
+    /// Every subset of `amount` items is equally likely and is returned in random order.
+    /// If `iter` yields fewer than `amount` items, all of them are returned.
+    ///
+    /// Uses Li's Algorithm L, which draws how many items to skip before the next replacement
+    /// instead of drawing once per item, so the generator is called `O(amount * log(n / amount))` times.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn sample_from_iter<I: IntoIterator>(
+        &mut self,
+        iter: I,
+        amount: usize,
+    ) -> RngResult<Vec<I::Item>> {
+        let mut iter = iter.into_iter();
+        let mut reservoir = iter.by_ref().take(amount).collect::<Vec<I::Item>>();
+        if reservoir.len() < amount || amount == 0 {
+            self.shuffle(&mut reservoir)?;
+            return Ok(reservoir);
+        }
+        let k = amount as f64;
+        let mut w = (self.random_unit_f64_open_closed()?.ln() / k).exp();
+        loop {
+            // `ln_1p` keeps `ln(1 - w)` exact once `w` is tiny on very long streams
+            let skip = (self.random_unit_f64_open_closed()?.ln() / (-w).ln_1p()).floor();
+            match iter.nth(skip as usize) {
+                Some(item) => {
+                    let slot = uniform::usize_below(self, amount)?;
+                    reservoir[slot] = item;
+                    w *= (self.random_unit_f64_open_closed()?.ln() / k).exp();
+                }
+                None => break,
+            }
+        }
+        self.shuffle(&mut reservoir)?;
+        Ok(reservoir)
+    }
+
+    /// Sample `amount` distinct indices from `0..length`, in random order.
+    ///
+    /// Picks the cheapest strategy for the ratio of `amount` to `length`: Floyd's algorithm for
+    /// few indices, a partial shuffle when a large share of `length` is wanted, and rejection
+    /// of duplicates with a hash set in between. Memory never exceeds `O(amount)` except for the
+    /// partial shuffle, which needs `O(length)` but only runs when `amount` is at least `length / 4`.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `amount` is larger than `length` or the generator fails.
+    fn sample_indices(&mut self, length: usize, amount: usize) -> RngResult<IndexVec> {
+        index::sample_indices(self, length, amount)
+    }
+
+    /// Draw one value from `distribution`.
+    ///
+    /// ## Example
+    /// ```
+    /// use tyche::{RngApiExt, Tyche};
+    /// use tyche::distributions::StandardNormal;
+    ///
+    /// let mut tyche = Tyche::new().unwrap();
+    /// let z: f64 = tyche.sample(&StandardNormal).unwrap();
+    /// assert!(z.is_finite());
+    /// ```
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    fn sample<T, D: Distribution<T>>(&mut self, distribution: &D) -> RngResult<T> {
+        distribution.sample(self)
+    }
+}
+
+impl<R: RngApi + ?Sized> RngApiExt for R {}
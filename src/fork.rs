@@ -0,0 +1,53 @@
+//! Detection of `fork()`, so a child process never replays buffered output of its parent.
+//!
+//! A `pthread_atfork` handler bumps a generation counter in every child. Generators holding
+//! buffered or derived state remember the generation they were seeded in and discard that
+//! state when it changes. Checking costs one atomic load.
+//!
+//! Processes created with a raw `clone` syscall bypass the handler and are not detected.
+
+use std::sync::Once;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(unix)]
+mod sys {
+    use std::ffi::c_int;
+    use std::sync::atomic::Ordering;
+
+    unsafe extern "C" {
+        fn pthread_atfork(
+            prepare: Option<unsafe extern "C" fn()>,
+            parent: Option<unsafe extern "C" fn()>,
+            child: Option<unsafe extern "C" fn()>,
+        ) -> c_int;
+    }
+
+    /// Runs in the child right after `fork()`, while it is still single threaded.
+    unsafe extern "C" fn child() {
+        super::GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn register() {
+        // SAFETY: `child` only touches an atomic, which is async-signal-safe as `fork()`
+        // requires. Registration can only fail with ENOMEM, leaving forks undetected.
+        unsafe { pthread_atfork(None, None, Some(child)) };
+    }
+}
+
+#[cfg(not(unix))]
+mod sys {
+    /// Stand-in for platforms without `fork()`.
+    pub(super) fn register() {}
+}
+
+/// The fork generation of this process, different in a child than in its parent.
+///
+/// The first call registers the `fork()` handler; generators call this when they are created,
+/// so every fork after that is seen.
+pub(crate) fn generation() -> u64 {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(sys::register);
+    GENERATION.load(Ordering::Relaxed)
+}
@@ -0,0 +1,250 @@
+//! Sampling distinct indices without replacement.
+
+use crate::RngApiExt;
+use crate::uniform;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::collections::HashSet;
+
+/// Distinct indices returned by [`sample_indices`](crate::RngApiExt::sample_indices).
+///
+/// Indices are stored as `u32` whenever the sampled length allows it, halving the memory
+/// needed on 64 bit targets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IndexVec {
+    U32(Vec<u32>),
+    USize(Vec<usize>),
+}
+
+impl IndexVec {
+    /// The number of indices.
+    pub fn len(&self) -> usize {
+        match self {
+            IndexVec::U32(v) => v.len(),
+            IndexVec::USize(v) => v.len(),
+        }
+    }
+
+    /// Whether there are no indices.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The index at position `position`.
+    ///
+    /// ## Panics
+    /// Panics if `position` is out of bounds.
+    pub fn index(&self, position: usize) -> usize {
+        match self {
+            IndexVec::U32(v) => v[position] as usize,
+            IndexVec::USize(v) => v[position],
+        }
+    }
+
+    /// Iterate over the indices.
+    pub fn iter(&self) -> IndexVecIter<'_> {
+        match self {
+            IndexVec::U32(v) => IndexVecIter::U32(v.iter()),
+            IndexVec::USize(v) => IndexVecIter::USize(v.iter()),
+        }
+    }
+
+    /// Convert into a `Vec<usize>`.
+    pub fn into_vec(self) -> Vec<usize> {
+        match self {
+            IndexVec::U32(v) => v.into_iter().map(|i| i as usize).collect(),
+            IndexVec::USize(v) => v,
+        }
+    }
+}
+
+/// Borrowing iterator over an [`IndexVec`].
+#[derive(Clone, Debug)]
+pub enum IndexVecIter<'a> {
+    U32(std::slice::Iter<'a, u32>),
+    USize(std::slice::Iter<'a, usize>),
+}
+
+impl Iterator for IndexVecIter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        match self {
+            IndexVecIter::U32(iter) => iter.next().map(|&i| i as usize),
+            IndexVecIter::USize(iter) => iter.next().copied(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            IndexVecIter::U32(iter) => iter.size_hint(),
+            IndexVecIter::USize(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl ExactSizeIterator for IndexVecIter<'_> {}
+
+/// Owning iterator over an [`IndexVec`].
+#[derive(Clone, Debug)]
+pub enum IndexVecIntoIter {
+    U32(std::vec::IntoIter<u32>),
+    USize(std::vec::IntoIter<usize>),
+}
+
+impl Iterator for IndexVecIntoIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        match self {
+            IndexVecIntoIter::U32(iter) => iter.next().map(|i| i as usize),
+            IndexVecIntoIter::USize(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            IndexVecIntoIter::U32(iter) => iter.size_hint(),
+            IndexVecIntoIter::USize(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl ExactSizeIterator for IndexVecIntoIter {}
+
+impl IntoIterator for IndexVec {
+    type Item = usize;
+    type IntoIter = IndexVecIntoIter;
+
+    fn into_iter(self) -> IndexVecIntoIter {
+        match self {
+            IndexVec::U32(v) => IndexVecIntoIter::U32(v.into_iter()),
+            IndexVec::USize(v) => IndexVecIntoIter::USize(v.into_iter()),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a IndexVec {
+    type Item = usize;
+    type IntoIter = IndexVecIter<'a>;
+
+    fn into_iter(self) -> IndexVecIter<'a> {
+        self.iter()
+    }
+}
+
+/// Up to this many indices, Floyd's algorithm with a linear duplicate check beats hashing.
+const FLOYD_MAX_AMOUNT: usize = 32;
+
+/// Sample `amount` distinct indices from `0..length` in random order.
+///
+/// - Few indices: Floyd's algorithm, `O(amount^2)` time and `O(amount)` memory.
+/// - A quarter of `length` or more: partial Fisher–Yates shuffle of `0..length`, `O(length)`.
+/// - Otherwise: draw and reject duplicates with a hash set, `O(amount)` expected.
+pub(crate) fn sample_indices<R: RngApi + ?Sized>(
+    rng: &mut R,
+    length: usize,
+    amount: usize,
+) -> RngResult<IndexVec> {
+    if amount > length {
+        return Err(RngError::Generic(format!(
+            "Cannot sample {amount} distinct indices out of {length}!"
+        )));
+    }
+    if length <= u32::MAX as usize {
+        let length = length as u32;
+        let amount = amount as u32;
+        Ok(IndexVec::U32(sample(rng, length, amount, |rng, n| {
+            uniform::u32_below(rng, n)
+        })?))
+    } else {
+        Ok(IndexVec::USize(sample(rng, length, amount, |rng, n| {
+            uniform::usize_below(rng, n)
+        })?))
+    }
+}
+
+/// Pick a strategy and sample, `below(rng, n)` draws uniformly from `[0, n)`.
+fn sample<R, T, F>(rng: &mut R, length: T, amount: T, below: F) -> RngResult<Vec<T>>
+where
+    R: RngApi + ?Sized,
+    T: Index,
+    F: Fn(&mut R, T) -> RngResult<T>,
+{
+    if amount.as_usize() <= FLOYD_MAX_AMOUNT {
+        floyd(rng, length, amount, below)
+    } else if amount.as_usize() >= length.as_usize() / 4 {
+        let mut indices = (0..length.as_usize())
+            .map(T::from_usize)
+            .collect::<Vec<T>>();
+        rng.partial_shuffle(&mut indices, amount.as_usize())?;
+        indices.truncate(amount.as_usize());
+        Ok(indices)
+    } else {
+        rejection(rng, length, amount, below)
+    }
+}
+
+/// Floyd's algorithm, for each `j` in `length - amount..length` pick `t` in `[0, j]` and take it,
+/// or `j` if `t` was taken already.
+///
+/// Taking `j` in place of the earlier `t` and appending `t` keeps the order uniformly random too.
+fn floyd<R, T, F>(rng: &mut R, length: T, amount: T, below: F) -> RngResult<Vec<T>>
+where
+    R: RngApi + ?Sized,
+    T: Index,
+    F: Fn(&mut R, T) -> RngResult<T>,
+{
+    let mut indices = Vec::with_capacity(amount.as_usize());
+    for j in length.as_usize() - amount.as_usize()..length.as_usize() {
+        let t = below(rng, T::from_usize(j + 1))?;
+        if let Some(position) = indices.iter().position(|&i| i == t) {
+            indices[position] = T::from_usize(j);
+        }
+        indices.push(t);
+    }
+    Ok(indices)
+}
+
+/// Draw uniformly and skip duplicates until `amount` distinct indices were found.
+fn rejection<R, T, F>(rng: &mut R, length: T, amount: T, below: F) -> RngResult<Vec<T>>
+where
+    R: RngApi + ?Sized,
+    T: Index,
+    F: Fn(&mut R, T) -> RngResult<T>,
+{
+    let mut seen = HashSet::with_capacity(amount.as_usize());
+    let mut indices = Vec::with_capacity(amount.as_usize());
+    while indices.len() < amount.as_usize() {
+        let t = below(rng, length)?;
+        if seen.insert(t) {
+            indices.push(t);
+        }
+    }
+    Ok(indices)
+}
+
+/// The integer types an [`IndexVec`] stores.
+trait Index: Copy + Eq + std::hash::Hash {
+    fn as_usize(self) -> usize;
+    fn from_usize(n: usize) -> Self;
+}
+
+impl Index for u32 {
+    fn as_usize(self) -> usize {
+        self as usize
+    }
+
+    fn from_usize(n: usize) -> Self {
+        n as u32
+    }
+}
+
+impl Index for usize {
+    fn as_usize(self) -> usize {
+        self
+    }
+
+    fn from_usize(n: usize) -> Self {
+        n
+    }
+}
@@ -10,288 +10,142 @@
 //! ## Returns
 //!
 //! All functions return a `Result()`. This is because of the random number generator used on the backend. It can run out of entropy, something that is highly unlikely but possible, or the program can not open `/dev/urandom`. If you get a `Err()` back the second reason is the most likely candidate as I have not encountered one `Err()` value not caused by this or improper calling by supplying bad arguments.
+//!
+//! ## Backends
+//!
+//! On Linux, `Tyche` reads its entropy through the `getrandom(2)` syscall, so no file descriptor is needed and it keeps working inside chroots, sandboxes without `/dev` and processes that hit their fd limit.
+//! If the kernel does not know the syscall (`ENOSYS`), `Tyche` falls back to reading `/dev/urandom`.
+//!
+//! Other sources can be plugged in through the [`EntropySource`] trait, see [`Tyche::with_source`] and [`Tyche::from_reader`].
 
 #![allow(clippy::needless_doctest_main)]
 #[cfg(test)]
 mod tests;
 #[cfg(test)]
 mod examples;
-
-use athena::rng_api::{RngApi, RngError, RngResult};
-use std::fs::File;
+mod entropy;
+mod ext;
+mod fork;
+mod index;
+mod macros;
+mod os;
+mod pool;
+mod reseeding;
+mod thread;
+mod uniform;
+mod weighted;
+
+pub mod distributions;
+pub mod drbg;
+pub mod rngs;
+
+pub use entropy::{EntropySource, GetRandom, ReaderSource, Urandom};
+pub use ext::RngApiExt;
+pub use index::{IndexVec, IndexVecIntoIter, IndexVecIter};
+pub use reseeding::ReseedingRng;
+pub use thread::{ThreadRng, thread_rng};
+pub use uniform::SampleUniform;
+pub use weighted::{Weight, WeightError, WeightedIndex};
+
+use athena::rng_api::RngApi;
+use macros::rng_api_methods;
+use pool::{DEFAULT_POOL_SIZE, Pool};
 use std::io::{Error as IoError, Read};
-use std::ops::{Add, Sub};
 
-/// A CSPRNG implementation using `/dev/urandom`.
-pub struct Tyche(File);
+/// A CSPRNG implementation reading from an [`EntropySource`].
+///
+/// By default the source is [`GetRandom`], the `getrandom(2)` syscall with a `/dev/urandom` fallback.
+///
+/// Small requests are served from an internal buffer that is refilled from the source in one
+/// read, so generating a `u8` does not cost a syscall each time. Bytes are wiped from the buffer
+/// as soon as they are handed out. See [`Tyche::with_buffer_size`].
+pub struct Tyche<S: EntropySource = GetRandom> {
+    source: S,
+    pool: Pool,
+}
 
 impl Tyche {
     /// Create a new Tyche instance.
     ///
+    /// Uses the `getrandom(2)` syscall if the kernel supports it, `/dev/urandom` otherwise.
+    /// Reads are buffered, 256 bytes at a time.
+    ///
     /// ## Errors
-    /// Returns `IoError` if `/dev/urandom` cannot be opened.
+    /// Returns `IoError` if `getrandom(2)` is unavailable and `/dev/urandom` cannot be opened.
     pub fn new() -> Result<Self, IoError> {
-        Ok(Tyche(File::open("/dev/urandom")?))
+        Ok(Tyche::with_source(GetRandom::new()?).with_buffer_size(DEFAULT_POOL_SIZE))
     }
 }
 
-impl RngApi for Tyche {
-    fn random_u8(&mut self) -> RngResult<u8> {
-        let mut buffer = [0u8; 1];
-        self.0.read_exact(&mut buffer)?;
-        Ok(buffer[0])
-    }
-
-    fn random_u16(&mut self) -> RngResult<u16> {
-        let mut buffer = [0u8; 2];
-        self.0.read_exact(&mut buffer)?;
-        Ok(u16::from_le_bytes(buffer))
-    }
-
-    fn random_u32(&mut self) -> RngResult<u32> {
-        let mut buffer = [0u8; 4];
-        self.0.read_exact(&mut buffer)?;
-        Ok(u32::from_le_bytes(buffer))
-    }
-
-    fn random_u64(&mut self) -> RngResult<u64> {
-        let mut buffer = [0u8; 8];
-        self.0.read_exact(&mut buffer)?;
-        Ok(u64::from_le_bytes(buffer))
-    }
-
-    fn random_usize(&mut self) -> RngResult<usize> {
-        #[cfg(target_pointer_width = "64")]
-        {
-            Ok(self.random_u64()? as usize)
-        }
-        #[cfg(target_pointer_width = "32")]
-        {
-            Ok(self.random_u32()? as usize)
-        }
-    }
-
-    fn random_i8(&mut self) -> RngResult<i8> {
-        let mut buffer = [0u8; 1];
-        self.0.read_exact(&mut buffer)?;
-        Ok(i8::from_le_bytes(buffer))
-    }
-
-    fn random_i16(&mut self) -> RngResult<i16> {
-        let mut buffer = [0u8; 2];
-        self.0.read_exact(&mut buffer)?;
-        Ok(i16::from_le_bytes(buffer))
-    }
-
-    fn random_i32(&mut self) -> RngResult<i32> {
-        let mut buffer = [0u8; 4];
-        self.0.read_exact(&mut buffer)?;
-        Ok(i32::from_le_bytes(buffer))
-    }
-
-    fn random_i64(&mut self) -> RngResult<i64> {
-        let mut buffer = [0u8; 8];
-        self.0.read_exact(&mut buffer)?;
-        Ok(i64::from_le_bytes(buffer))
-    }
-
-    fn random_f32(&mut self) -> RngResult<f32> {
-        let mut buffer = [0u8; 4];
-        self.0.read_exact(&mut buffer)?;
-        let out = f32::from_le_bytes(buffer);
-        if out.is_nan() {
-            self.random_f32()
-        } else {
-            Ok(out)
-        }
-    }
-
-    fn random_f64(&mut self) -> RngResult<f64> {
-        let mut buffer = [0u8; 8];
-        self.0.read_exact(&mut buffer)?;
-        let out = f64::from_le_bytes(buffer);
-        if out.is_nan() {
-            self.random_f64()
-        } else {
-            Ok(out)
-        }
-    }
-
-    fn random_bytes(&mut self, len: usize) -> RngResult<Vec<u8>> {
-        let mut buffer = vec![0u8; len];
-        self.0.read_exact(&mut buffer)?;
-        Ok(buffer)
-    }
-
-    fn random_string(&mut self, len: usize) -> RngResult<String> {
-        let mut out = String::with_capacity(len);
-        for _ in 0..len {
-            out.push(self.random_ascii_char()?);
-        }
-        Ok(out)
-    }
-
-    fn random_latin_char(&mut self, uppercase: bool) -> RngResult<char> {
-        let chars = [
-            'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q',
-            'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
-        ];
-        let idx = self.random_index(chars.len())?;
-        let chosen_char = chars[idx];
-        if uppercase {
-            Ok(chosen_char.to_ascii_uppercase())
-        } else {
-            Ok(chosen_char)
-        }
-    }
-
-    fn random_ascii_char(&mut self) -> RngResult<char> {
-        let val = self.random_from_range_inclusive(32, 126)?;
-        Ok(val as u8 as char)
-    }
-
-    fn random_bool(&mut self) -> RngResult<bool> {
-        let b = self.random_u8()?;
-        Ok((b & 1) == 1)
-    }
-
-    fn random_from_range_inclusive(&mut self, min: usize, max: usize) -> RngResult<usize> {
-        if min < max {
-            let range_size = max.saturating_sub(min).saturating_add(1);
-            let rnd = self.random_usize()?;
-            Ok(min.saturating_add(rnd % range_size))
-        } else if min == max {
-            Ok(min)
-        } else {
-            Err(RngError::Generic(format!(
-                "Min '{min}' is larger than max '{max}'!"
-            )))
-        }
-    }
-
-    fn random_from_range(&mut self, min: usize, max: usize) -> RngResult<usize> {
-        if min < max {
-            let range_size = max.saturating_sub(min);
-            let rnd = self.random_usize()?;
-            Ok(min.saturating_add(rnd % range_size))
-        } else if min == max {
-            Ok(min)
-        } else {
-            Err(RngError::Generic(format!(
-                "Min '{min}' is larger than max '{max}'!"
-            )))
-        }
-    }
-
-    fn random_from_u64_range(&mut self, min: u64, max: u64) -> RngResult<u64> {
-        if min < max {
-            let range_size = max.saturating_sub(min);
-            let rnd = self.random_u64()?;
-            Ok(min.saturating_add(rnd % range_size))
-        } else if min == max {
-            Ok(min)
-        } else {
-            Err(RngError::Generic(format!(
-                "Min '{min}' is larger than max '{max}'!"
-            )))
-        }
-    }
-
-    fn random_from_i_range(&mut self, min: isize, max: isize) -> RngResult<isize> {
-        if min < max {
-            let range_size = (max - min) as usize;
-            let rnd = self.random_usize()?;
-            Ok(min + (rnd % range_size) as isize)
-        } else if min == max {
-            Ok(min)
-        } else {
-            Err(RngError::Generic(format!(
-                "Min '{min}' is larger than max '{max}'!"
-            )))
-        }
+impl Tyche<Urandom> {
+    /// Create a new Tyche instance reading from `/dev/urandom`, even if `getrandom(2)` is available.
+    /// Reads are buffered, 256 bytes at a time.
+    ///
+    /// ## Errors
+    /// Returns `IoError` if `/dev/urandom` cannot be opened.
+    pub fn from_urandom() -> Result<Self, IoError> {
+        Ok(Tyche::with_source(Urandom::new()?).with_buffer_size(DEFAULT_POOL_SIZE))
     }
+}
 
-    fn random_from_i64_range(&mut self, min: i64, max: i64) -> RngResult<i64> {
-        if min < max {
-            let range_size = (max - min) as u64;
-            let rnd = self.random_u64()?;
-            Ok(min + (rnd % range_size) as i64)
-        } else if min == max {
-            Ok(min)
-        } else {
-            Err(RngError::Generic(format!(
-                "Min '{min}' is larger than max '{max}'!"
-            )))
-        }
+impl<R: Read> Tyche<ReaderSource<R>> {
+    /// Create a new, unbuffered Tyche instance drawing its entropy from `reader`.
+    pub fn from_reader(reader: R) -> Self {
+        Tyche::with_source(ReaderSource::new(reader))
     }
+}
 
-    fn random_from_i32_range(&mut self, min: i32, max: i32) -> RngResult<i32> {
-        if min < max {
-            let range_size = (max - min) as u32;
-            let rnd = self.random_u32()?;
-            Ok(min + (rnd % range_size) as i32)
-        } else if min == max {
-            Ok(min)
-        } else {
-            Err(RngError::Generic(format!(
-                "Min '{min}' is larger than max '{max}'!"
-            )))
+impl<S: EntropySource> Tyche<S> {
+    /// Create a new Tyche instance drawing its entropy from `source`.
+    ///
+    /// The instance is unbuffered, every request reads exactly the bytes it needs from `source`.
+    /// Use [`Tyche::with_buffer_size`] to read ahead.
+    pub fn with_source(source: S) -> Self {
+        Tyche {
+            source,
+            pool: Pool::new(0),
         }
     }
 
-    fn random_from_f32_range(&mut self, min: f32, max: f32) -> RngResult<f32> {
-        if min < max {
-            let range_size = max.sub(min);
-            let rng = self.random_f32()?;
-            let random_index = rng.abs() % range_size;
-            Ok(min.add(random_index))
-        } else if min == max {
-            Ok(min)
-        } else {
-            Err(RngError::Generic(format!(
-                "Min '{min}' is larger than max '{max}'!"
-            )))
-        }
+    /// Set the size of the internal entropy buffer in bytes, discarding anything already buffered.
+    ///
+    /// Requests smaller than `size` are served from the buffer, larger ones read the source directly.
+    /// A size of 0 disables buffering.
+    pub fn with_buffer_size(mut self, size: usize) -> Self {
+        self.pool = Pool::new(size);
+        self
     }
 
-    fn random_from_f64_range(&mut self, min: f64, max: f64) -> RngResult<f64> {
-        if min < max {
-            let range_size = max.sub(min);
-            let rng = self.random_f64()?;
-            let random_index = rng.abs() % range_size;
-            Ok(min.add(random_index))
-        } else if min == max {
-            Ok(min)
-        } else {
-            Err(RngError::Generic(format!(
-                "Min '{min}' is larger than max '{max}'!"
-            )))
-        }
+    /// The size of the internal entropy buffer in bytes, 0 if unbuffered.
+    pub fn buffer_size(&self) -> usize {
+        self.pool.size()
     }
 
-    fn random_index(&mut self, collection_length: usize) -> RngResult<usize> {
-        if collection_length >= 1 {
-            self.random_with_ceiling(collection_length.saturating_sub(1))
-        } else {
-            Err(RngError::Generic(format!(
-                "collection length '{collection_length}' is less than 1!"
-            )))
-        }
+    /// Return the underlying entropy source, wiping any buffered entropy.
+    pub fn into_source(self) -> S {
+        self.source
     }
 
-    fn random_with_ceiling(&mut self, max: usize) -> RngResult<usize> {
-        self.random_from_range_inclusive(usize::MIN, max)
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), IoError> {
+        self.pool.fill(&mut self.source, buffer)
     }
+}
 
-    fn random_with_floor(&mut self, min: usize) -> RngResult<usize> {
-        self.random_from_range_inclusive(min, usize::MAX)
-    }
+impl<S: EntropySource> RngApi for Tyche<S> {
+    rng_api_methods!();
 }
 
+/// Free functions for quick access to random values.
+///
+/// Every function draws from the calling thread's shared `Tyche` instance, see [`thread_rng`].
 pub mod prelude {
-    use super::Tyche;
+    pub use super::distributions::Distribution;
+    pub use super::{RngApiExt, thread_rng};
+    use super::IndexVec;
+    use super::uniform::{self, SampleUniform};
     use athena::rng_api::{RngApi, RngError};
     use std::io::Error as IoError;
+    use std::ops::RangeBounds;
 
     fn to_io_error(e: RngError) -> IoError {
         match e {
@@ -301,59 +155,80 @@ pub mod prelude {
     }
 
     pub fn random_u8() -> Result<u8, IoError> {
-        Tyche::new()?.random_u8().map_err(to_io_error)
+        thread_rng().random_u8().map_err(to_io_error)
     }
 
     pub fn random_u16() -> Result<u16, IoError> {
-        Tyche::new()?.random_u16().map_err(to_io_error)
+        thread_rng().random_u16().map_err(to_io_error)
     }
 
     pub fn random_u32() -> Result<u32, IoError> {
-        Tyche::new()?.random_u32().map_err(to_io_error)
+        thread_rng().random_u32().map_err(to_io_error)
     }
 
     pub fn random_u64() -> Result<u64, IoError> {
-        Tyche::new()?.random_u64().map_err(to_io_error)
+        thread_rng().random_u64().map_err(to_io_error)
+    }
+
+    pub fn random_u128() -> Result<u128, IoError> {
+        thread_rng().random_u128().map_err(to_io_error)
     }
 
     pub fn random_i8() -> Result<i8, IoError> {
-        Tyche::new()?.random_i8().map_err(to_io_error)
+        thread_rng().random_i8().map_err(to_io_error)
     }
 
     pub fn random_i32() -> Result<i32, IoError> {
-        Tyche::new()?.random_i32().map_err(to_io_error)
+        thread_rng().random_i32().map_err(to_io_error)
+    }
+
+    pub fn random_i128() -> Result<i128, IoError> {
+        thread_rng().random_i128().map_err(to_io_error)
     }
 
     pub fn random_f32() -> Result<f32, IoError> {
-        Tyche::new()?.random_f32().map_err(to_io_error)
+        thread_rng().random_f32().map_err(to_io_error)
+    }
+
+    pub fn random_unit_f32() -> Result<f32, IoError> {
+        thread_rng().random_unit_f32().map_err(to_io_error)
+    }
+
+    pub fn random_unit_f64() -> Result<f64, IoError> {
+        thread_rng().random_unit_f64().map_err(to_io_error)
     }
 
     pub fn random_string() -> Result<String, IoError> {
         // Compatibility: the old random_string was weirdly specific,
         // but let's just return a random string of length 10.
-        Tyche::new()?.random_string(10).map_err(to_io_error)
+        thread_rng().random_string(10).map_err(to_io_error)
     }
 
     pub fn random_latin_char(uppercase: bool) -> Result<char, IoError> {
-        Tyche::new()?.random_latin_char(uppercase).map_err(to_io_error)
+        thread_rng().random_latin_char(uppercase).map_err(to_io_error)
     }
 
     pub fn random_bool() -> Result<bool, IoError> {
-        Tyche::new()?.random_bool().map_err(to_io_error)
+        thread_rng().random_bool().map_err(to_io_error)
+    }
+
+    /// Generate a `bool` that is `true` with probability `p`, exactly. See [`RngApiExt::random_bool_with_probability`].
+    pub fn random_bool_with_probability(p: f64) -> Result<bool, IoError> {
+        thread_rng()
+            .random_bool_with_probability(p)
+            .map_err(to_io_error)
     }
 
     pub fn random_from_range(start: usize, end: usize) -> Result<usize, IoError> {
         // The old implementation was inclusive on both ends for random_from_range
-        Tyche::new()?.random_from_range_inclusive(start, end).map_err(to_io_error)
+        thread_rng().random_from_range_inclusive(start, end).map_err(to_io_error)
     }
 
     pub fn random_from_u64range(start: u64, end: u64) -> Result<u64, IoError> {
         // The old implementation was inclusive on both ends
-        let mut t = Tyche::new()?;
         if start < end {
-            let range_size = end.saturating_sub(start).saturating_add(1);
-            let rnd = t.random_u64().map_err(to_io_error)?;
-            Ok(start.saturating_add(rnd % range_size))
+            let rnd = uniform::u64_up_to(&mut thread_rng(), end - start).map_err(to_io_error)?;
+            Ok(start + rnd)
         } else if start == end {
             Ok(start)
         } else {
@@ -361,17 +236,27 @@ pub mod prelude {
         }
     }
 
+    pub fn random_from_u128range(start: u128, end: u128) -> Result<u128, IoError> {
+        // Inclusive on both ends, like the other prelude ranges
+        if start <= end {
+            thread_rng()
+                .random_from_u128_range_inclusive(start, end)
+                .map_err(to_io_error)
+        } else {
+            Err(IoError::other(format!("Start '{start}' is larger than end '{end}'!")))
+        }
+    }
+
     pub fn random_from_f32range(start: f32, end: f32) -> Result<f32, IoError> {
-        Tyche::new()?.random_from_f32_range(start, end).map_err(to_io_error)
+        thread_rng().random_from_f32_range(start, end).map_err(to_io_error)
     }
 
     pub fn random_from_i32range(start: i32, end: i32) -> Result<i32, IoError> {
         // The old implementation was inclusive on both ends
-        let mut t = Tyche::new()?;
         if start < end {
-            let range_size = (end as i64 - start as i64 + 1) as u64;
-            let rnd = t.random_u32().map_err(to_io_error)? as u64;
-            Ok((start as i64 + (rnd % range_size) as i64) as i32)
+            let range_size = (end as i64 - start as i64) as u32;
+            let rnd = uniform::u32_up_to(&mut thread_rng(), range_size).map_err(to_io_error)?;
+            Ok((start as i64 + rnd as i64) as i32)
         } else if start == end {
             Ok(start)
         } else {
@@ -379,15 +264,70 @@ pub mod prelude {
         }
     }
 
+    /// Generate a random value uniformly distributed over `range`.
+    ///
+    /// Unlike `random_from_range`, the bounds follow the range syntax: `a..b` excludes `b`, `a..=b` includes it.
+    /// See [`RngApiExt::gen_range`].
+    pub fn gen_range<T: SampleUniform, B: RangeBounds<T>>(range: B) -> Result<T, IoError> {
+        thread_rng().gen_range(range).map_err(to_io_error)
+    }
+
+    /// Shuffle `slice` in place, every permutation being equally likely. See [`RngApiExt::shuffle`].
+    pub fn shuffle<T>(slice: &mut [T]) -> Result<(), IoError> {
+        thread_rng().shuffle(slice).map_err(to_io_error)
+    }
+
+    /// Move `amount` randomly chosen elements of `slice` to its front. See [`RngApiExt::partial_shuffle`].
+    pub fn partial_shuffle<T>(
+        slice: &mut [T],
+        amount: usize,
+    ) -> Result<(&mut [T], &mut [T]), IoError> {
+        thread_rng().partial_shuffle(slice, amount).map_err(to_io_error)
+    }
+
+    /// Choose a uniformly random element of `slice`. See [`RngApiExt::choose`].
+    pub fn choose<T>(slice: &[T]) -> Result<&T, IoError> {
+        thread_rng().choose(slice).map_err(to_io_error)
+    }
+
+    /// Choose `amount` distinct elements of `slice`. See [`RngApiExt::choose_multiple`].
+    pub fn choose_multiple<T>(slice: &[T], amount: usize) -> Result<Vec<&T>, IoError> {
+        thread_rng().choose_multiple(slice, amount).map_err(to_io_error)
+    }
+
+    /// Choose an element of `slice` with probability proportional to its weight. See [`RngApiExt::choose_weighted`].
+    pub fn choose_weighted<T, F: FnMut(&T) -> f64>(slice: &[T], weight: F) -> Result<&T, IoError> {
+        thread_rng().choose_weighted(slice, weight).map_err(to_io_error)
+    }
+
+    /// Choose a uniformly random item of `iter` in a single pass. See [`RngApiExt::choose_from_iter`].
+    pub fn choose_from_iter<I: IntoIterator>(iter: I) -> Result<I::Item, IoError> {
+        thread_rng().choose_from_iter(iter).map_err(to_io_error)
+    }
+
+    /// Choose `amount` distinct items of `iter` in a single pass. See [`RngApiExt::sample_from_iter`].
+    pub fn sample_from_iter<I: IntoIterator>(iter: I, amount: usize) -> Result<Vec<I::Item>, IoError> {
+        thread_rng().sample_from_iter(iter, amount).map_err(to_io_error)
+    }
+
+    /// Sample `amount` distinct indices from `0..length`. See [`RngApiExt::sample_indices`].
+    pub fn sample_indices(length: usize, amount: usize) -> Result<IndexVec, IoError> {
+        thread_rng().sample_indices(length, amount).map_err(to_io_error)
+    }
+
     pub fn random_index(collection_length: usize) -> Result<usize, IoError> {
-        Tyche::new()?.random_index(collection_length).map_err(to_io_error)
+        thread_rng().random_index(collection_length).map_err(to_io_error)
     }
 
     pub fn random_with_ceiling(ceiling: usize) -> Result<usize, IoError> {
-        Tyche::new()?.random_with_ceiling(ceiling).map_err(to_io_error)
+        thread_rng().random_with_ceiling(ceiling).map_err(to_io_error)
+    }
+
+    pub fn random_u128_with_ceiling(ceiling: u128) -> Result<u128, IoError> {
+        thread_rng().random_u128_with_ceiling(ceiling).map_err(to_io_error)
     }
 
     pub fn random_with_floor(floor: usize) -> Result<usize, IoError> {
-        Tyche::new()?.random_with_floor(floor).map_err(to_io_error)
+        thread_rng().random_with_floor(floor).map_err(to_io_error)
     }
 }
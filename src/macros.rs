@@ -0,0 +1,285 @@
+//! Macros shared by the generators in this crate.
+
+/// Expands to every `RngApi` method, built on an inherent
+/// `fn fill(&mut self, buffer: &mut [u8]) -> Result<(), E>` where `RngError: From<E>`.
+///
+/// Use inside an `impl RngApi for ...` block.
+macro_rules! rng_api_methods {
+    () => {
+        fn random_u8(&mut self) -> ::athena::rng_api::RngResult<u8> {
+            let mut buffer = [0u8; 1];
+            self.fill(&mut buffer)?;
+            Ok(buffer[0])
+        }
+
+        fn random_u16(&mut self) -> ::athena::rng_api::RngResult<u16> {
+            let mut buffer = [0u8; 2];
+            self.fill(&mut buffer)?;
+            Ok(u16::from_le_bytes(buffer))
+        }
+
+        fn random_u32(&mut self) -> ::athena::rng_api::RngResult<u32> {
+            let mut buffer = [0u8; 4];
+            self.fill(&mut buffer)?;
+            Ok(u32::from_le_bytes(buffer))
+        }
+
+        fn random_u64(&mut self) -> ::athena::rng_api::RngResult<u64> {
+            let mut buffer = [0u8; 8];
+            self.fill(&mut buffer)?;
+            Ok(u64::from_le_bytes(buffer))
+        }
+
+        fn random_usize(&mut self) -> ::athena::rng_api::RngResult<usize> {
+            #[cfg(target_pointer_width = "64")]
+            {
+                Ok(self.random_u64()? as usize)
+            }
+            #[cfg(target_pointer_width = "32")]
+            {
+                Ok(self.random_u32()? as usize)
+            }
+        }
+
+        fn random_i8(&mut self) -> ::athena::rng_api::RngResult<i8> {
+            let mut buffer = [0u8; 1];
+            self.fill(&mut buffer)?;
+            Ok(i8::from_le_bytes(buffer))
+        }
+
+        fn random_i16(&mut self) -> ::athena::rng_api::RngResult<i16> {
+            let mut buffer = [0u8; 2];
+            self.fill(&mut buffer)?;
+            Ok(i16::from_le_bytes(buffer))
+        }
+
+        fn random_i32(&mut self) -> ::athena::rng_api::RngResult<i32> {
+            let mut buffer = [0u8; 4];
+            self.fill(&mut buffer)?;
+            Ok(i32::from_le_bytes(buffer))
+        }
+
+        fn random_i64(&mut self) -> ::athena::rng_api::RngResult<i64> {
+            let mut buffer = [0u8; 8];
+            self.fill(&mut buffer)?;
+            Ok(i64::from_le_bytes(buffer))
+        }
+
+        fn random_f32(&mut self) -> ::athena::rng_api::RngResult<f32> {
+            let mut buffer = [0u8; 4];
+            self.fill(&mut buffer)?;
+            let out = f32::from_le_bytes(buffer);
+            if out.is_nan() {
+                self.random_f32()
+            } else {
+                Ok(out)
+            }
+        }
+
+        fn random_f64(&mut self) -> ::athena::rng_api::RngResult<f64> {
+            let mut buffer = [0u8; 8];
+            self.fill(&mut buffer)?;
+            let out = f64::from_le_bytes(buffer);
+            if out.is_nan() {
+                self.random_f64()
+            } else {
+                Ok(out)
+            }
+        }
+
+        fn random_bytes(&mut self, len: usize) -> ::athena::rng_api::RngResult<Vec<u8>> {
+            let mut buffer = vec![0u8; len];
+            self.fill(&mut buffer)?;
+            Ok(buffer)
+        }
+
+        fn random_string(&mut self, len: usize) -> ::athena::rng_api::RngResult<String> {
+            let mut out = String::with_capacity(len);
+            for _ in 0..len {
+                out.push(self.random_ascii_char()?);
+            }
+            Ok(out)
+        }
+
+        fn random_latin_char(&mut self, uppercase: bool) -> ::athena::rng_api::RngResult<char> {
+            let chars = [
+                'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p',
+                'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+            ];
+            let idx = self.random_index(chars.len())?;
+            let chosen_char = chars[idx];
+            if uppercase {
+                Ok(chosen_char.to_ascii_uppercase())
+            } else {
+                Ok(chosen_char)
+            }
+        }
+
+        fn random_ascii_char(&mut self) -> ::athena::rng_api::RngResult<char> {
+            let val = self.random_from_range_inclusive(32, 126)?;
+            Ok(val as u8 as char)
+        }
+
+        fn random_bool(&mut self) -> ::athena::rng_api::RngResult<bool> {
+            let b = self.random_u8()?;
+            Ok((b & 1) == 1)
+        }
+
+        fn random_from_range_inclusive(
+            &mut self,
+            min: usize,
+            max: usize,
+        ) -> ::athena::rng_api::RngResult<usize> {
+            if min < max {
+                Ok(min + $crate::uniform::usize_up_to(self, max - min)?)
+            } else if min == max {
+                Ok(min)
+            } else {
+                Err(::athena::rng_api::RngError::Generic(format!(
+                    "Min '{min}' is larger than max '{max}'!"
+                )))
+            }
+        }
+
+        fn random_from_range(
+            &mut self,
+            min: usize,
+            max: usize,
+        ) -> ::athena::rng_api::RngResult<usize> {
+            if min < max {
+                Ok(min + $crate::uniform::usize_below(self, max - min)?)
+            } else if min == max {
+                Ok(min)
+            } else {
+                Err(::athena::rng_api::RngError::Generic(format!(
+                    "Min '{min}' is larger than max '{max}'!"
+                )))
+            }
+        }
+
+        fn random_from_u64_range(
+            &mut self,
+            min: u64,
+            max: u64,
+        ) -> ::athena::rng_api::RngResult<u64> {
+            if min < max {
+                Ok(min + $crate::uniform::u64_below(self, max - min)?)
+            } else if min == max {
+                Ok(min)
+            } else {
+                Err(::athena::rng_api::RngError::Generic(format!(
+                    "Min '{min}' is larger than max '{max}'!"
+                )))
+            }
+        }
+
+        fn random_from_i_range(
+            &mut self,
+            min: isize,
+            max: isize,
+        ) -> ::athena::rng_api::RngResult<isize> {
+            if min < max {
+                // Two's complement: the difference is exact when read as unsigned
+                let range_size = max.wrapping_sub(min) as usize;
+                Ok(min.wrapping_add($crate::uniform::usize_below(self, range_size)? as isize))
+            } else if min == max {
+                Ok(min)
+            } else {
+                Err(::athena::rng_api::RngError::Generic(format!(
+                    "Min '{min}' is larger than max '{max}'!"
+                )))
+            }
+        }
+
+        fn random_from_i64_range(
+            &mut self,
+            min: i64,
+            max: i64,
+        ) -> ::athena::rng_api::RngResult<i64> {
+            if min < max {
+                // Two's complement: the difference is exact when read as unsigned
+                let range_size = max.wrapping_sub(min) as u64;
+                Ok(min.wrapping_add($crate::uniform::u64_below(self, range_size)? as i64))
+            } else if min == max {
+                Ok(min)
+            } else {
+                Err(::athena::rng_api::RngError::Generic(format!(
+                    "Min '{min}' is larger than max '{max}'!"
+                )))
+            }
+        }
+
+        fn random_from_i32_range(
+            &mut self,
+            min: i32,
+            max: i32,
+        ) -> ::athena::rng_api::RngResult<i32> {
+            if min < max {
+                // Two's complement: the difference is exact when read as unsigned
+                let range_size = max.wrapping_sub(min) as u32;
+                Ok(min.wrapping_add($crate::uniform::u32_below(self, range_size)? as i32))
+            } else if min == max {
+                Ok(min)
+            } else {
+                Err(::athena::rng_api::RngError::Generic(format!(
+                    "Min '{min}' is larger than max '{max}'!"
+                )))
+            }
+        }
+
+        fn random_from_f32_range(
+            &mut self,
+            min: f32,
+            max: f32,
+        ) -> ::athena::rng_api::RngResult<f32> {
+            if min < max {
+                $crate::uniform::f32_in(self, min, max)
+            } else if min == max {
+                Ok(min)
+            } else {
+                Err(::athena::rng_api::RngError::Generic(format!(
+                    "Min '{min}' is larger than max '{max}'!"
+                )))
+            }
+        }
+
+        fn random_from_f64_range(
+            &mut self,
+            min: f64,
+            max: f64,
+        ) -> ::athena::rng_api::RngResult<f64> {
+            if min < max {
+                $crate::uniform::f64_in(self, min, max)
+            } else if min == max {
+                Ok(min)
+            } else {
+                Err(::athena::rng_api::RngError::Generic(format!(
+                    "Min '{min}' is larger than max '{max}'!"
+                )))
+            }
+        }
+
+        fn random_index(
+            &mut self,
+            collection_length: usize,
+        ) -> ::athena::rng_api::RngResult<usize> {
+            if collection_length >= 1 {
+                self.random_with_ceiling(collection_length.saturating_sub(1))
+            } else {
+                Err(::athena::rng_api::RngError::Generic(format!(
+                    "collection length '{collection_length}' is less than 1!"
+                )))
+            }
+        }
+
+        fn random_with_ceiling(&mut self, max: usize) -> ::athena::rng_api::RngResult<usize> {
+            self.random_from_range_inclusive(usize::MIN, max)
+        }
+
+        fn random_with_floor(&mut self, min: usize) -> ::athena::rng_api::RngResult<usize> {
+            self.random_from_range_inclusive(min, usize::MAX)
+        }
+    };
+}
+
+pub(crate) use rng_api_methods;
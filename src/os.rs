@@ -0,0 +1,149 @@
+//! Bindings to the Linux `getrandom(2)` syscall.
+//!
+//! The syscall is issued directly through libc's `syscall()` so no file descriptor is needed.
+//! On targets where the syscall number is unknown, or on kernels older than 3.17, every call
+//! reports `ENOSYS` and callers are expected to fall back to `/dev/urandom`.
+
+use std::io::Error as IoError;
+use std::sync::OnceLock;
+
+/// `ENOSYS`: the kernel does not implement the syscall.
+pub(crate) const ENOSYS: i32 = 38;
+/// `EINTR`: the syscall was interrupted by a signal before any bytes were written.
+const EINTR: i32 = 4;
+
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86_64",
+        target_arch = "x86",
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "riscv64",
+        target_arch = "powerpc64",
+        target_arch = "s390x",
+        target_arch = "loongarch64"
+    )
+))]
+pub(crate) mod sys {
+    use std::ffi::{c_long, c_uint};
+    use std::io::Error as IoError;
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_GETRANDOM: c_long = 318;
+    #[cfg(target_arch = "x86")]
+    const SYS_GETRANDOM: c_long = 355;
+    #[cfg(any(
+        target_arch = "aarch64",
+        target_arch = "riscv64",
+        target_arch = "loongarch64"
+    ))]
+    const SYS_GETRANDOM: c_long = 278;
+    #[cfg(target_arch = "arm")]
+    const SYS_GETRANDOM: c_long = 384;
+    #[cfg(target_arch = "powerpc64")]
+    const SYS_GETRANDOM: c_long = 359;
+    #[cfg(target_arch = "s390x")]
+    const SYS_GETRANDOM: c_long = 349;
+
+    unsafe extern "C" {
+        fn syscall(number: c_long, ...) -> c_long;
+    }
+
+    /// Raw `getrandom(buf, len, 0)`. Returns the number of bytes written or the `errno`.
+    pub(crate) fn getrandom(buf: &mut [u8]) -> Result<usize, IoError> {
+        // SAFETY: the kernel writes at most `buf.len()` bytes into `buf`, which is valid and
+        // exclusively borrowed for the duration of the call.
+        let ret = unsafe { syscall(SYS_GETRANDOM, buf.as_mut_ptr(), buf.len(), 0 as c_uint) };
+        if ret < 0 {
+            Err(IoError::last_os_error())
+        } else {
+            Ok(ret as usize)
+        }
+    }
+}
+
+/// Stand-in for platforms without a known `getrandom` syscall.
+///
+/// Also compiled for tests so the fallback path can be exercised on every target.
+#[cfg(any(
+    test,
+    not(all(
+        target_os = "linux",
+        any(
+            target_arch = "x86_64",
+            target_arch = "x86",
+            target_arch = "aarch64",
+            target_arch = "arm",
+            target_arch = "riscv64",
+            target_arch = "powerpc64",
+            target_arch = "s390x",
+            target_arch = "loongarch64"
+        )
+    ))
+))]
+pub(crate) mod unsupported {
+    use super::ENOSYS;
+    use std::io::Error as IoError;
+
+    /// Always fails with `ENOSYS`, exactly like a kernel that predates the syscall.
+    pub(crate) fn getrandom(_buf: &mut [u8]) -> Result<usize, IoError> {
+        Err(IoError::from_raw_os_error(ENOSYS))
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86_64",
+        target_arch = "x86",
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "riscv64",
+        target_arch = "powerpc64",
+        target_arch = "s390x",
+        target_arch = "loongarch64"
+    )
+)))]
+pub(crate) use unsupported as sys;
+
+/// Signature shared by the real syscall and the `ENOSYS` stand-in.
+pub(crate) type GetrandomFn = fn(&mut [u8]) -> Result<usize, IoError>;
+
+/// Fill `buf` completely using `getrandom(2)`.
+///
+/// Short reads and `EINTR` are retried until the buffer is full.
+///
+/// ## Errors
+/// Returns the `errno` of the failing syscall, `ENOSYS` if the kernel lacks `getrandom`.
+pub(crate) fn getrandom_fill(buf: &mut [u8]) -> Result<(), IoError> {
+    fill_with(sys::getrandom, buf)
+}
+
+/// `getrandom_fill` over an explicit syscall implementation.
+pub(crate) fn fill_with(getrandom: GetrandomFn, mut buf: &mut [u8]) -> Result<(), IoError> {
+    while !buf.is_empty() {
+        match getrandom(buf) {
+            Ok(written) => buf = &mut buf[written..],
+            Err(err) if err.raw_os_error() == Some(EINTR) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Whether the running kernel supports `getrandom(2)`.
+///
+/// Probed once per process with a zero length request, which succeeds without blocking.
+pub(crate) fn getrandom_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| probe(sys::getrandom))
+}
+
+/// Whether `getrandom` is usable: anything but `ENOSYS` means the kernel knows the syscall.
+pub(crate) fn probe(getrandom: GetrandomFn) -> bool {
+    match getrandom(&mut []) {
+        Ok(_) => true,
+        Err(err) => err.raw_os_error() != Some(ENOSYS),
+    }
+}
@@ -0,0 +1,89 @@
+//! A refillable entropy buffer, so small requests do not cost one `read()` each.
+
+use crate::EntropySource;
+use crate::fork;
+use std::io::Error as IoError;
+
+/// Default pool size of a [`Tyche`](crate::Tyche) reading from the operating system.
+///
+/// `getrandom(2)` guarantees that requests of up to 256 bytes are never interrupted or shortened.
+pub(crate) const DEFAULT_POOL_SIZE: usize = 256;
+
+/// Overwrite `buffer` with zeros in a way the optimiser will not elide.
+pub(crate) fn wipe<T: Copy + Default>(buffer: &mut [T]) {
+    for item in buffer.iter_mut() {
+        // SAFETY: `item` is a valid, aligned and exclusively borrowed `T`.
+        unsafe { std::ptr::write_volatile(item, T::default()) };
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Entropy read ahead from a source, handed out front to back.
+///
+/// Bytes are wiped as soon as they are handed out and the whole buffer is wiped on drop,
+/// so no entropy lingers in memory after it has been used. A forked child discards the
+/// buffer it inherited instead of handing out the same bytes as its parent.
+pub(crate) struct Pool {
+    buffer: Box<[u8]>,
+    /// Index of the first unused byte; `buffer.len()` when the pool is empty.
+    pos: usize,
+    /// The fork generation the buffered bytes were read in.
+    generation: u64,
+}
+
+impl Pool {
+    /// Create an empty pool holding up to `size` bytes. A size of 0 disables buffering.
+    pub(crate) fn new(size: usize) -> Self {
+        Pool {
+            buffer: vec![0u8; size].into_boxed_slice(),
+            pos: size,
+            generation: fork::generation(),
+        }
+    }
+
+    pub(crate) fn size(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Fill `out` from the pool, refilling it from `source` as needed.
+    ///
+    /// Requests at least as large as the pool bypass it and go straight to `source`.
+    pub(crate) fn fill<S: EntropySource>(
+        &mut self,
+        source: &mut S,
+        out: &mut [u8],
+    ) -> Result<(), IoError> {
+        if out.len() >= self.buffer.len() {
+            return source.fill_bytes(out);
+        }
+        let generation = fork::generation();
+        if generation != self.generation {
+            wipe(&mut self.buffer);
+            self.pos = self.buffer.len();
+            self.generation = generation;
+        }
+        let mut written = 0;
+        while written < out.len() {
+            if self.pos == self.buffer.len() {
+                if let Err(e) = source.fill_bytes(&mut self.buffer) {
+                    wipe(&mut self.buffer);
+                    return Err(e);
+                }
+                self.pos = 0;
+            }
+            let n = (out.len() - written).min(self.buffer.len() - self.pos);
+            let taken = &mut self.buffer[self.pos..self.pos + n];
+            out[written..written + n].copy_from_slice(taken);
+            wipe(taken);
+            self.pos += n;
+            written += n;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        wipe(&mut self.buffer);
+    }
+}
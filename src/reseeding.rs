@@ -0,0 +1,205 @@
+//! A userspace CSPRNG that reseeds itself from an entropy source.
+
+use crate::fork;
+use crate::macros::rng_api_methods;
+use crate::pool::wipe;
+use crate::rngs::ChaCha20Rng;
+use crate::{EntropySource, GetRandom};
+use athena::rng_api::RngApi;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::io::Error as IoError;
+use std::time::{Duration, Instant};
+
+const KEY_LEN: usize = 32;
+
+/// Keystream generated per refill: 12 ChaCha blocks, the first 32 bytes of which become the
+/// next key.
+const BUFFER_LEN: usize = 768;
+
+/// A ChaCha20 CSPRNG in userspace, seeded from an [`EntropySource`] and reseeded
+/// automatically.
+///
+/// Output is generated with "fast key erasure": every refill produces a batch of ChaCha20
+/// keystream, immediately replaces the key with the first 32 bytes of it and hands out the
+/// rest. Bytes are wiped as soon as they are handed out, so neither the current key nor
+/// memory can reveal output that was already returned.
+///
+/// Before a refill, the generator mixes 32 fresh bytes from its source into the key once
+/// [`reseed_bytes`](Self::with_reseed_bytes) bytes have been generated or
+/// [`reseed_interval`](Self::with_reseed_interval) has passed since the last reseed. A forked
+/// child reseeds before its first output, so it never repeats the output of its parent.
+///
+/// Like [`Tyche`](crate::Tyche) it implements `RngApi`, but only touches the source once per
+/// reseed instead of once per buffer, so it runs close to the speed of ChaCha20.
+pub struct ReseedingRng<S: EntropySource = GetRandom> {
+    source: S,
+    state: State,
+    reseed_bytes: u64,
+    reseed_interval: Duration,
+    bytes_since_reseed: u64,
+    last_reseed: Instant,
+    /// The fork generation of the last reseed.
+    generation: u64,
+}
+
+/// The secret part of a [`ReseedingRng`], wiped on drop.
+struct State {
+    key: [u8; KEY_LEN],
+    buffer: [u8; BUFFER_LEN],
+    /// Index of the first unused byte; `BUFFER_LEN` when the buffer is empty.
+    pos: usize,
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        wipe(&mut self.key);
+        wipe(&mut self.buffer);
+    }
+}
+
+impl ReseedingRng {
+    /// Create a generator seeded through `getrandom(2)`, reseeding every
+    /// [`DEFAULT_RESEED_BYTES`](Self::DEFAULT_RESEED_BYTES) bytes or
+    /// [`DEFAULT_RESEED_INTERVAL`](Self::DEFAULT_RESEED_INTERVAL).
+    ///
+    /// ## Errors
+    /// Returns `IoError` if the operating system fails to provide the seed.
+    pub fn new() -> Result<Self, IoError> {
+        ReseedingRng::with_source(GetRandom::new()?)
+    }
+}
+
+impl<S: EntropySource> ReseedingRng<S> {
+    /// The default number of bytes generated between two reseeds, 1 MiB.
+    pub const DEFAULT_RESEED_BYTES: u64 = 1 << 20;
+
+    /// The default time between two reseeds, one minute.
+    pub const DEFAULT_RESEED_INTERVAL: Duration = Duration::from_secs(60);
+
+    /// Create a generator seeded from `source`, which is also used for every reseed.
+    ///
+    /// ## Errors
+    /// Returns `IoError` if `source` fails to provide the seed.
+    pub fn with_source(source: S) -> Result<Self, IoError> {
+        let mut rng = ReseedingRng {
+            source,
+            state: State {
+                key: [0; KEY_LEN],
+                buffer: [0; BUFFER_LEN],
+                pos: BUFFER_LEN,
+            },
+            reseed_bytes: Self::DEFAULT_RESEED_BYTES,
+            reseed_interval: Self::DEFAULT_RESEED_INTERVAL,
+            bytes_since_reseed: 0,
+            last_reseed: Instant::now(),
+            generation: fork::generation(),
+        };
+        rng.reseed()?;
+        Ok(rng)
+    }
+
+    /// Reseed after `bytes` have been generated.
+    ///
+    /// The check runs whenever the internal buffer is refilled, so up to 736 more bytes may be
+    /// generated before the reseed happens.
+    pub fn with_reseed_bytes(mut self, bytes: u64) -> Self {
+        self.reseed_bytes = bytes;
+        self
+    }
+
+    /// Reseed once `interval` has passed since the last reseed.
+    ///
+    /// The check runs whenever the internal buffer is refilled, so an idle generator reseeds
+    /// on its next use.
+    pub fn with_reseed_interval(mut self, interval: Duration) -> Self {
+        self.reseed_interval = interval;
+        self
+    }
+
+    /// The number of bytes generated between two reseeds.
+    pub fn reseed_bytes(&self) -> u64 {
+        self.reseed_bytes
+    }
+
+    /// The time between two reseeds.
+    pub fn reseed_interval(&self) -> Duration {
+        self.reseed_interval
+    }
+
+    /// Mix 32 fresh bytes from the source into the key now, discarding buffered output.
+    ///
+    /// ## Errors
+    /// Returns `IoError` if the source fails; the generator is left unchanged.
+    pub fn reseed(&mut self) -> Result<(), IoError> {
+        let mut entropy = [0u8; KEY_LEN];
+        let result = self.source.fill_bytes(&mut entropy);
+        if result.is_ok() {
+            for (key, fresh) in self.state.key.iter_mut().zip(entropy) {
+                *key ^= fresh;
+            }
+            wipe(&mut self.state.buffer);
+            self.state.pos = BUFFER_LEN;
+            self.bytes_since_reseed = 0;
+            self.last_reseed = Instant::now();
+            self.generation = fork::generation();
+        }
+        wipe(&mut entropy);
+        result
+    }
+
+    /// Return the underlying entropy source, wiping the key and any buffered output.
+    pub fn into_source(self) -> S {
+        self.source
+    }
+
+    /// Generate the next batch of keystream, reseeding first if it is due.
+    fn refill(&mut self) -> Result<(), IoError> {
+        if self.bytes_since_reseed >= self.reseed_bytes
+            || self.last_reseed.elapsed() >= self.reseed_interval
+        {
+            self.reseed()?;
+        }
+        let state = &mut self.state;
+        let mut chacha = ChaCha20Rng::from_key(&state.key);
+        chacha.fill_bytes(&mut state.buffer);
+        drop(chacha);
+        state.key.copy_from_slice(&state.buffer[..KEY_LEN]);
+        wipe(&mut state.buffer[..KEY_LEN]);
+        state.pos = KEY_LEN;
+        self.bytes_since_reseed += (BUFFER_LEN - KEY_LEN) as u64;
+        Ok(())
+    }
+
+    fn fill(&mut self, out: &mut [u8]) -> Result<(), IoError> {
+        if fork::generation() != self.generation {
+            self.reseed()?;
+        }
+        let mut written = 0;
+        while written < out.len() {
+            if self.state.pos == BUFFER_LEN {
+                self.refill()?;
+            }
+            let pos = self.state.pos;
+            let n = (out.len() - written).min(BUFFER_LEN - pos);
+            let taken = &mut self.state.buffer[pos..pos + n];
+            out[written..written + n].copy_from_slice(taken);
+            wipe(taken);
+            self.state.pos += n;
+            written += n;
+        }
+        Ok(())
+    }
+}
+
+impl<S: EntropySource> RngApi for ReseedingRng<S> {
+    rng_api_methods!();
+}
+
+impl<S: EntropySource> Debug for ReseedingRng<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("ReseedingRng")
+            .field("reseed_bytes", &self.reseed_bytes)
+            .field("reseed_interval", &self.reseed_interval)
+            .finish_non_exhaustive()
+    }
+}
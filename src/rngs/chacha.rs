@@ -0,0 +1,247 @@
+//! The ChaCha stream cipher as a seedable generator.
+
+use super::SeedableRng;
+use super::state::codec::Codec;
+use super::state::read_u64;
+use crate::macros::rng_api_methods;
+use crate::pool::wipe;
+use athena::rng_api::{RngApi, RngResult};
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+/// "expand 32-byte k"
+const SIGMA: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+const BLOCK_WORDS: usize = 16;
+
+/// A generator built on the ChaCha stream cipher by D. J. Bernstein, with `ROUNDS` rounds.
+///
+/// The output is the ChaCha keystream for a 32 byte key, so it is cryptographically secure
+/// as long as the seed is secret; use [`ChaCha20Rng`] unless speed matters more than margin.
+/// Like the original cipher, state words 12 and 13 hold a 64 bit block counter and words 14
+/// and 15 a 64 bit stream id: every key gives `2^64` independent streams of `2^68` words each.
+///
+/// Output is consumed in whole 32 bit words, a `random_u8` uses up a word of the stream.
+///
+/// ## Example
+/// ```
+/// use athena::rng_api::RngApi;
+/// use tyche::Tyche;
+/// use tyche::rngs::{ChaCha20Rng, SeedableRng};
+///
+/// let mut rng = ChaCha20Rng::from_rng(&mut Tyche::new().unwrap()).unwrap();
+/// let seed = rng.get_seed();
+/// let first = rng.random_u64().unwrap();
+///
+/// // The same seed replays the same stream
+/// let mut replay = ChaCha20Rng::from_seed(seed);
+/// assert_eq!(replay.random_u64().unwrap(), first);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct ChaChaRng<const ROUNDS: usize = 20> {
+    key: [u32; 8],
+    stream: u64,
+    /// The counter of the block in `buffer`.
+    block: u64,
+    buffer: [u32; BLOCK_WORDS],
+    /// Index of the next unused word in `buffer`; `BLOCK_WORDS` when it is used up.
+    index: usize,
+}
+
+/// ChaCha with 8 rounds, the fastest variant with no known practical attack.
+pub type ChaCha8Rng = ChaChaRng<8>;
+/// ChaCha with 12 rounds.
+pub type ChaCha12Rng = ChaChaRng<12>;
+/// ChaCha with 20 rounds, as standardised in RFC 8439.
+pub type ChaCha20Rng = ChaChaRng<20>;
+
+impl<const ROUNDS: usize> ChaChaRng<ROUNDS> {
+    /// The seed this generator was created from.
+    pub fn get_seed(&self) -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        for (chunk, word) in seed.chunks_exact_mut(4).zip(self.key) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        seed
+    }
+
+    /// The stream id, 0 unless changed with [`set_stream`](Self::set_stream).
+    pub fn get_stream(&self) -> u64 {
+        self.stream
+    }
+
+    /// Switch to stream `stream`, keeping the word position.
+    ///
+    /// Different streams of the same seed are independent, which allows giving every thread
+    /// or simulation run its own stream of one seed.
+    pub fn set_stream(&mut self, stream: u64) {
+        self.stream = stream;
+        self.refill();
+    }
+
+    /// The index of the next 32 bit word of the stream, counting from 0.
+    ///
+    /// Wraps at `2^68`, after which the stream repeats.
+    pub fn get_word_pos(&self) -> u128 {
+        ((self.block as u128) * BLOCK_WORDS as u128 + self.index as u128) % (1 << 68)
+    }
+
+    /// Seek to 32 bit word `word_pos` of the stream, modulo `2^68`.
+    pub fn set_word_pos(&mut self, word_pos: u128) {
+        self.block = (word_pos / BLOCK_WORDS as u128) as u64;
+        self.index = (word_pos % BLOCK_WORDS as u128) as usize;
+        self.refill();
+    }
+
+    /// The next 32 bit word of the stream.
+    pub fn next_u32(&mut self) -> u32 {
+        if self.index == BLOCK_WORDS {
+            self.block = self.block.wrapping_add(1);
+            self.index = 0;
+            self.refill();
+        }
+        let word = self.buffer[self.index];
+        self.index += 1;
+        word
+    }
+
+    /// The next two words of the stream, the first as the low half.
+    pub fn next_u64(&mut self) -> u64 {
+        let low = self.next_u32() as u64;
+        low | (self.next_u32() as u64) << 32
+    }
+
+    /// Fill `buffer` with the stream, in little endian byte order.
+    ///
+    /// A trailing partial word discards its unused bytes.
+    pub fn fill_bytes(&mut self, buffer: &mut [u8]) {
+        for chunk in buffer.chunks_mut(4) {
+            let word = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+    }
+
+    /// Like `from_seed`, but reads the key in place so that no copy of it is left behind.
+    pub(crate) fn from_key(seed: &[u8; 32]) -> Self {
+        const {
+            assert!(
+                ROUNDS == 8 || ROUNDS == 12 || ROUNDS == 20,
+                "ChaCha is defined for 8, 12 or 20 rounds"
+            )
+        };
+        let mut rng = ChaChaRng {
+            key: [0; 8],
+            stream: 0,
+            block: 0,
+            buffer: [0; BLOCK_WORDS],
+            index: 0,
+        };
+        for (word, chunk) in rng.key.iter_mut().zip(seed.chunks_exact(4)) {
+            *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        rng.refill();
+        rng
+    }
+
+    fn fill(&mut self, buffer: &mut [u8]) -> RngResult<()> {
+        self.fill_bytes(buffer);
+        Ok(())
+    }
+
+    /// Compute block `self.block` into the buffer.
+    fn refill(&mut self) {
+        let mut state = [0u32; BLOCK_WORDS];
+        state[..4].copy_from_slice(&SIGMA);
+        state[4..12].copy_from_slice(&self.key);
+        state[12] = self.block as u32;
+        state[13] = (self.block >> 32) as u32;
+        state[14] = self.stream as u32;
+        state[15] = (self.stream >> 32) as u32;
+
+        let mut x = state;
+        for _ in 0..ROUNDS / 2 {
+            // Column round
+            quarter_round(&mut x, 0, 4, 8, 12);
+            quarter_round(&mut x, 1, 5, 9, 13);
+            quarter_round(&mut x, 2, 6, 10, 14);
+            quarter_round(&mut x, 3, 7, 11, 15);
+            // Diagonal round
+            quarter_round(&mut x, 0, 5, 10, 15);
+            quarter_round(&mut x, 1, 6, 11, 12);
+            quarter_round(&mut x, 2, 7, 8, 13);
+            quarter_round(&mut x, 3, 4, 9, 14);
+        }
+        for ((out, x), s) in self.buffer.iter_mut().zip(x).zip(state) {
+            *out = x.wrapping_add(s);
+        }
+        wipe(&mut state);
+        wipe(&mut x);
+    }
+}
+
+fn quarter_round(x: &mut [u32; BLOCK_WORDS], a: usize, b: usize, c: usize, d: usize) {
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(16);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(12);
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(8);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(7);
+}
+
+impl<const ROUNDS: usize> SeedableRng for ChaChaRng<ROUNDS> {
+    type Seed = [u8; 32];
+
+    /// Use `seed` as the ChaCha key, starting at word 0 of stream 0.
+    fn from_seed(seed: [u8; 32]) -> Self {
+        Self::from_key(&seed)
+    }
+}
+
+impl<const ROUNDS: usize> RngApi for ChaChaRng<ROUNDS> {
+    rng_api_methods!();
+}
+
+impl<const ROUNDS: usize> Codec for ChaChaRng<ROUNDS> {
+    const ID: u8 = match ROUNDS {
+        8 => 1,
+        12 => 2,
+        _ => 3,
+    };
+    const PAYLOAD_LEN: usize = 49;
+
+    fn write_payload(&self, out: &mut Vec<u8>) {
+        let mut seed = self.get_seed();
+        out.extend_from_slice(&seed);
+        wipe(&mut seed);
+        out.extend_from_slice(&self.stream.to_le_bytes());
+        out.extend_from_slice(&self.block.to_le_bytes());
+        out.push(self.index as u8);
+    }
+
+    fn read_payload(payload: &[u8]) -> Option<Self> {
+        let index = payload[48] as usize;
+        if index > BLOCK_WORDS {
+            return None;
+        }
+        let mut rng = Self::from_seed(payload[..32].try_into().unwrap());
+        rng.stream = read_u64(payload, 4);
+        rng.block = read_u64(payload, 5);
+        rng.index = index;
+        rng.refill();
+        Some(rng)
+    }
+}
+
+impl<const ROUNDS: usize> Debug for ChaChaRng<ROUNDS> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        // The key and buffer are secret
+        write!(f, "ChaChaRng<{ROUNDS}> {{ .. }}")
+    }
+}
+
+impl<const ROUNDS: usize> Drop for ChaChaRng<ROUNDS> {
+    fn drop(&mut self) {
+        wipe(&mut self.key);
+        wipe(&mut self.buffer);
+    }
+}
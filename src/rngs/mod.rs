@@ -0,0 +1,104 @@
+//! Seedable, deterministic generators implementing [`RngApi`].
+//!
+//! Unlike [`Tyche`](crate::Tyche), which reads fresh entropy from the operating system, these
+//! generators expand a seed into a stream that is identical on every run. Use them for
+//! reproducible tests and simulations, and seed them from `Tyche` when reproducibility is
+//! not needed.
+//!
+//! ## Cryptographic generators
+//! [`ChaChaRng`] is a stream cipher: its output cannot be predicted without the seed, so it
+//! may be used for keys, tokens and nonces as long as the seed is secret.
+//!
+//! ## Fast generators
+//! [`Xoshiro256StarStar`], [`Xoshiro128PlusPlus`], [`Pcg32`], [`Pcg64`] and [`SplitMix64`]
+//! are **not cryptographically secure**: a few outputs are enough to reconstruct their state
+//! and predict everything that follows. In exchange they need a few nanoseconds per value,
+//! which makes them the right choice for procedural generation, simulations and
+//! benchmarks, and the wrong one for anything an attacker may want to guess. The xoshiro
+//! generators can jump ahead to split their period into independent sequences, the PCG and
+//! SplitMix generators can [`advance`](Pcg64::advance) by any number of steps.
+//!
+//! ## Checkpoints
+//! Every generator here implements [`SaveState`]: [`save_state`](SaveState::save_state)
+//! encodes its exact state in a small, versioned binary format and
+//! [`from_state`](SaveState::from_state) restores it, so a pre-empted simulation can resume
+//! with the identical stream.
+//!
+//! ## Example
+//! ```
+//! use athena::rng_api::RngApi;
+//! use tyche::rngs::{ChaCha20Rng, SeedableRng};
+//!
+//! let mut a = ChaCha20Rng::seed_from_u64(42);
+//! let mut b = ChaCha20Rng::seed_from_u64(42);
+//! assert_eq!(a.random_u64().unwrap(), b.random_u64().unwrap());
+//! ```
+
+#[cfg(test)]
+mod tests;
+
+mod chacha;
+mod pcg;
+mod splitmix;
+mod state;
+mod xoshiro;
+
+pub use chacha::{ChaCha8Rng, ChaCha12Rng, ChaCha20Rng, ChaChaRng};
+pub use pcg::{Pcg32, Pcg64};
+pub use splitmix::SplitMix64;
+pub use state::{SaveState, StateError};
+pub use xoshiro::{Xoshiro128PlusPlus, Xoshiro256StarStar};
+
+use crate::pool::wipe;
+use athena::rng_api::{RngApi, RngResult};
+use splitmix::splitmix64;
+
+/// A generator that can be created from a seed, producing the same stream for the same seed.
+pub trait SeedableRng: Sized {
+    /// The seed, a byte array.
+    type Seed: Default + AsMut<[u8]>;
+
+    /// Create the generator from a full seed.
+    fn from_seed(seed: Self::Seed) -> Self;
+
+    /// Create the generator from a `u64`, expanded into a full seed with SplitMix64.
+    ///
+    /// Convenient for tests and simulations, but only `2^64` of all streams can be reached,
+    /// so never use it to seed a generator protecting secrets.
+    fn seed_from_u64(state: u64) -> Self {
+        let mut seed = Self::Seed::default();
+        let mut state = state;
+        for chunk in seed.as_mut().chunks_mut(8) {
+            let word = splitmix64(&mut state).to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+        Self::from_seed(seed)
+    }
+
+    /// Create the generator with a seed drawn from `rng`, for example a
+    /// [`Tyche`](crate::Tyche).
+    ///
+    /// ## Errors
+    /// Returns `RngError` if `rng` fails.
+    fn from_rng<R: RngApi + ?Sized>(rng: &mut R) -> RngResult<Self> {
+        let mut seed = Self::Seed::default();
+        let mut bytes = rng.random_bytes(seed.as_mut().len())?;
+        seed.as_mut().copy_from_slice(&bytes);
+        wipe(&mut bytes);
+        Ok(Self::from_seed(seed))
+    }
+}
+
+/// Fill `buffer` with little endian words from `next`, discarding the unused bytes of the last.
+fn fill_via_u64(buffer: &mut [u8], mut next: impl FnMut() -> u64) {
+    for chunk in buffer.chunks_mut(8) {
+        chunk.copy_from_slice(&next().to_le_bytes()[..chunk.len()]);
+    }
+}
+
+/// Fill `buffer` with little endian words from `next`, discarding the unused bytes of the last.
+fn fill_via_u32(buffer: &mut [u8], mut next: impl FnMut() -> u32) {
+    for chunk in buffer.chunks_mut(4) {
+        chunk.copy_from_slice(&next().to_le_bytes()[..chunk.len()]);
+    }
+}
@@ -0,0 +1,234 @@
+//! The PCG generators by O'Neill.
+//!
+//! See "PCG: A Family of Simple Fast Space-Efficient Statistically Good Algorithms for Random
+//! Number Generation", 2014, and <https://www.pcg-random.org>.
+
+use super::state::codec::Codec;
+use super::state::read_u64;
+use super::{SeedableRng, fill_via_u32, fill_via_u64};
+use crate::macros::rng_api_methods;
+use athena::rng_api::{RngApi, RngResult};
+
+const MULTIPLIER_64: u64 = 6_364_136_223_846_793_005;
+const MULTIPLIER_128: u128 = 0x2360_ed05_1fc6_5da4_4385_df64_9fcc_f645;
+
+/// PCG32 (XSH RR 64/32): a 64 bit LCG with a xorshift and random rotation output function.
+///
+/// **Not cryptographically secure.** Small and fast, with `2^63` selectable streams of period
+/// `2^64` each. [`advance`](Self::advance) skips ahead in logarithmic time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+    /// Odd, selects the stream.
+    increment: u64,
+}
+
+impl Pcg32 {
+    /// Create the generator for `state` on stream `stream`, like `pcg32_srandom_r`.
+    ///
+    /// The top bit of `stream` is ignored.
+    pub fn new(state: u64, stream: u64) -> Self {
+        let increment = stream << 1 | 1;
+        let mut pcg = Pcg32 {
+            state: state.wrapping_add(increment),
+            increment,
+        };
+        pcg.step();
+        pcg
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(MULTIPLIER_64)
+            .wrapping_add(self.increment);
+    }
+
+    /// The next 32 bits.
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    /// The next two outputs, the first as the low half.
+    pub fn next_u64(&mut self) -> u64 {
+        let low = self.next_u32() as u64;
+        low | (self.next_u32() as u64) << 32
+    }
+
+    /// Fill `buffer` with output in little endian byte order.
+    ///
+    /// A trailing partial word discards its unused bytes.
+    pub fn fill_bytes(&mut self, buffer: &mut [u8]) {
+        fill_via_u32(buffer, || self.next_u32());
+    }
+
+    /// Skip the next `delta` outputs in `O(log delta)` time.
+    pub fn advance(&mut self, delta: u64) {
+        let (mult, plus) = lcg_jump_64(MULTIPLIER_64, self.increment, delta);
+        self.state = self.state.wrapping_mul(mult).wrapping_add(plus);
+    }
+
+    fn fill(&mut self, buffer: &mut [u8]) -> RngResult<()> {
+        self.fill_bytes(buffer);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Pcg32 {
+    type Seed = [u8; 16];
+
+    /// The first 8 bytes are the state and the last 8 the stream, see [`Pcg32::new`].
+    fn from_seed(seed: [u8; 16]) -> Self {
+        let (state, stream) = seed.split_at(8);
+        Pcg32::new(
+            u64::from_le_bytes(state.try_into().unwrap()),
+            u64::from_le_bytes(stream.try_into().unwrap()),
+        )
+    }
+}
+
+impl RngApi for Pcg32 {
+    rng_api_methods!();
+}
+
+impl Codec for Pcg32 {
+    const ID: u8 = 6;
+    const PAYLOAD_LEN: usize = 16;
+
+    fn write_payload(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.state.to_le_bytes());
+        out.extend_from_slice(&self.increment.to_le_bytes());
+    }
+
+    /// An even increment cannot come from [`Pcg32::new`] and is rejected.
+    fn read_payload(payload: &[u8]) -> Option<Self> {
+        let increment = read_u64(payload, 1);
+        (increment & 1 == 1).then_some(Pcg32 {
+            state: read_u64(payload, 0),
+            increment,
+        })
+    }
+}
+
+/// PCG64 (XSL RR 128/64): a 128 bit LCG with a xor-fold and random rotation output function.
+///
+/// **Not cryptographically secure.** `2^127` selectable streams of period `2^128` each.
+/// [`advance`](Self::advance) skips ahead in logarithmic time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pcg64 {
+    state: u128,
+    /// Odd, selects the stream.
+    increment: u128,
+}
+
+impl Pcg64 {
+    /// Create the generator for `state` on stream `stream`, like the reference `pcg64`.
+    ///
+    /// The top bit of `stream` is ignored.
+    pub fn new(state: u128, stream: u128) -> Self {
+        let increment = stream << 1 | 1;
+        let mut pcg = Pcg64 {
+            state: state.wrapping_add(increment),
+            increment,
+        };
+        pcg.step();
+        pcg
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(MULTIPLIER_128)
+            .wrapping_add(self.increment);
+    }
+
+    /// The next 64 bits.
+    pub fn next_u64(&mut self) -> u64 {
+        // Unlike PCG32, the 128 bit variant outputs from the new state
+        self.step();
+        let folded = (self.state >> 64) as u64 ^ self.state as u64;
+        folded.rotate_right((self.state >> 122) as u32)
+    }
+
+    /// Fill `buffer` with output in little endian byte order.
+    ///
+    /// A trailing partial word discards its unused bytes.
+    pub fn fill_bytes(&mut self, buffer: &mut [u8]) {
+        fill_via_u64(buffer, || self.next_u64());
+    }
+
+    /// Skip the next `delta` outputs in `O(log delta)` time.
+    pub fn advance(&mut self, delta: u128) {
+        let (mult, plus) = lcg_jump_128(MULTIPLIER_128, self.increment, delta);
+        self.state = self.state.wrapping_mul(mult).wrapping_add(plus);
+    }
+
+    fn fill(&mut self, buffer: &mut [u8]) -> RngResult<()> {
+        self.fill_bytes(buffer);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Pcg64 {
+    type Seed = [u8; 32];
+
+    /// The first 16 bytes are the state and the last 16 the stream, see [`Pcg64::new`].
+    fn from_seed(seed: [u8; 32]) -> Self {
+        let (state, stream) = seed.split_at(16);
+        Pcg64::new(
+            u128::from_le_bytes(state.try_into().unwrap()),
+            u128::from_le_bytes(stream.try_into().unwrap()),
+        )
+    }
+}
+
+impl RngApi for Pcg64 {
+    rng_api_methods!();
+}
+
+impl Codec for Pcg64 {
+    const ID: u8 = 7;
+    const PAYLOAD_LEN: usize = 32;
+
+    fn write_payload(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.state.to_le_bytes());
+        out.extend_from_slice(&self.increment.to_le_bytes());
+    }
+
+    /// An even increment cannot come from [`Pcg64::new`] and is rejected.
+    fn read_payload(payload: &[u8]) -> Option<Self> {
+        let increment = u128::from_le_bytes(payload[16..].try_into().unwrap());
+        (increment & 1 == 1).then_some(Pcg64 {
+            state: u128::from_le_bytes(payload[..16].try_into().unwrap()),
+            increment,
+        })
+    }
+}
+
+/// Generates `lcg_jump_64` and `lcg_jump_128`: the multiplier and increment of `delta`
+/// steps of the LCG `x -> mult * x + plus`, by squaring (Brown, "Random Number Generation
+/// with Arbitrary Strides", 1994).
+macro_rules! lcg_jump {
+    ($name:ident, $t:ty) => {
+        fn $name(mut mult: $t, mut plus: $t, mut delta: $t) -> ($t, $t) {
+            let mut acc_mult: $t = 1;
+            let mut acc_plus: $t = 0;
+            while delta > 0 {
+                if delta & 1 == 1 {
+                    acc_mult = acc_mult.wrapping_mul(mult);
+                    acc_plus = acc_plus.wrapping_mul(mult).wrapping_add(plus);
+                }
+                plus = mult.wrapping_add(1).wrapping_mul(plus);
+                mult = mult.wrapping_mul(mult);
+                delta >>= 1;
+            }
+            (acc_mult, acc_plus)
+        }
+    };
+}
+
+lcg_jump!(lcg_jump_64, u64);
+lcg_jump!(lcg_jump_128, u128);
@@ -0,0 +1,86 @@
+//! The SplitMix64 generator.
+
+use super::state::codec::Codec;
+use super::state::read_u64;
+use super::{SeedableRng, fill_via_u64};
+use crate::macros::rng_api_methods;
+use athena::rng_api::{RngApi, RngResult};
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// One step of SplitMix64, advancing `state` and returning the next output.
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(GOLDEN_GAMMA);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// SplitMix64 by Steele, Lea & Flood, "Fast Splittable Pseudorandom Number Generators", 2014.
+///
+/// **Not cryptographically secure.** A 64 bit counter passed through a strong mixing
+/// function: tiny, fast, and any seed is fine, which makes it the usual way to expand a
+/// `u64` into the larger seeds of other generators. Its period is `2^64`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// The next 64 bits.
+    pub fn next_u64(&mut self) -> u64 {
+        splitmix64(&mut self.state)
+    }
+
+    /// Fill `buffer` with output in little endian byte order.
+    ///
+    /// A trailing partial word discards its unused bytes.
+    pub fn fill_bytes(&mut self, buffer: &mut [u8]) {
+        fill_via_u64(buffer, || self.next_u64());
+    }
+
+    /// Skip the next `delta` outputs in constant time.
+    pub fn advance(&mut self, delta: u64) {
+        self.state = self.state.wrapping_add(delta.wrapping_mul(GOLDEN_GAMMA));
+    }
+
+    fn fill(&mut self, buffer: &mut [u8]) -> RngResult<()> {
+        self.fill_bytes(buffer);
+        Ok(())
+    }
+}
+
+impl SeedableRng for SplitMix64 {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: [u8; 8]) -> Self {
+        SplitMix64 {
+            state: u64::from_le_bytes(seed),
+        }
+    }
+
+    /// Use `state` as the state directly, like the reference implementation.
+    fn seed_from_u64(state: u64) -> Self {
+        SplitMix64 { state }
+    }
+}
+
+impl RngApi for SplitMix64 {
+    rng_api_methods!();
+}
+
+impl Codec for SplitMix64 {
+    const ID: u8 = 8;
+    const PAYLOAD_LEN: usize = 8;
+
+    fn write_payload(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.state.to_le_bytes());
+    }
+
+    fn read_payload(payload: &[u8]) -> Option<Self> {
+        Some(SplitMix64 {
+            state: read_u64(payload, 0),
+        })
+    }
+}
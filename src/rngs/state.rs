@@ -0,0 +1,185 @@
+//! Saving and restoring the exact state of the seedable generators.
+
+use athena::rng_api::RngError;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The first bytes of every saved state.
+const MAGIC: [u8; 4] = *b"TYCH";
+
+/// The version of the format written by [`SaveState::save_state`].
+const VERSION: u8 = 1;
+
+/// Magic, version, generator id and payload length.
+const HEADER_LEN: usize = 8;
+
+const CHECKSUM_LEN: usize = 4;
+
+/// Why a saved state could not be restored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StateError {
+    /// The data does not start with the magic bytes `TYCH`.
+    BadMagic,
+    /// The data was written in a format version this version of tyche cannot read.
+    UnsupportedVersion(u8),
+    /// The data is truncated or has trailing bytes.
+    BadLength,
+    /// The checksum does not match, the data is corrupted.
+    BadChecksum,
+    /// The data holds the state of a different generator, identified by this id.
+    WrongGenerator(u8),
+    /// The payload is not a state the generator can be in.
+    BadPayload,
+}
+
+impl Display for StateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            StateError::BadMagic => write!(f, "The data is not a saved generator state!"),
+            StateError::UnsupportedVersion(version) => {
+                write!(f, "The state format version {version} is not supported!")
+            }
+            StateError::BadLength => write!(f, "The saved state has the wrong length!"),
+            StateError::BadChecksum => write!(f, "The saved state is corrupted!"),
+            StateError::WrongGenerator(id) => {
+                write!(f, "The saved state belongs to generator {id}!")
+            }
+            StateError::BadPayload => write!(f, "The saved state is invalid for this generator!"),
+        }
+    }
+}
+
+impl Error for StateError {}
+
+impl From<StateError> for RngError {
+    fn from(e: StateError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+pub(super) mod codec {
+    /// How a generator encodes its state, implemented by every seedable generator.
+    pub trait Codec: Sized {
+        /// The generator id in the header.
+        const ID: u8;
+        /// The exact length of the payload.
+        const PAYLOAD_LEN: usize;
+
+        /// Append the payload to `out`.
+        fn write_payload(&self, out: &mut Vec<u8>);
+
+        /// Decode a payload of `PAYLOAD_LEN` bytes, `None` if it is not a valid state.
+        fn read_payload(payload: &[u8]) -> Option<Self>;
+    }
+}
+
+/// Saving a generator to bytes and restoring it, for checkpointing long runs.
+///
+/// A restored generator continues with exactly the output the saved one would have produced
+/// next. The saved state of [`ChaChaRng`](super::ChaChaRng) contains its key, so treat it as
+/// carefully as the seed.
+///
+/// ## Format
+/// Version 1 of the format, with all integers in little endian byte order:
+///
+/// | Offset    | Length | Content                                          |
+/// |-----------|--------|--------------------------------------------------|
+/// | 0         | 4      | The magic bytes `TYCH`                           |
+/// | 4         | 1      | The format version, 1                            |
+/// | 5         | 1      | The generator id, see below                      |
+/// | 6         | 2      | The payload length `n`, `u16`                    |
+/// | 8         | `n`    | The payload                                      |
+/// | 8 + `n`   | 4      | CRC-32 (IEEE) of all preceding bytes, `u32`      |
+///
+/// | Id | Generator              | Payload                                                   |
+/// |----|------------------------|-----------------------------------------------------------|
+/// | 1  | `ChaCha8Rng`           | key (32 bytes), stream `u64`, block `u64`, word index `u8` |
+/// | 2  | `ChaCha12Rng`          | as id 1                                                   |
+/// | 3  | `ChaCha20Rng`          | as id 1                                                   |
+/// | 4  | `Xoshiro256StarStar`   | `s[0]` to `s[3]`, 4 × `u64`, not all zero                 |
+/// | 5  | `Xoshiro128PlusPlus`   | `s[0]` to `s[3]`, 4 × `u32`, not all zero                 |
+/// | 6  | `Pcg32`                | state `u64`, increment `u64` (odd)                        |
+/// | 7  | `Pcg64`                | state `u128`, increment `u128` (odd)                      |
+/// | 8  | `SplitMix64`           | state `u64`                                               |
+///
+/// The ChaCha block is the counter of the block the next word comes from, the word index
+/// (0 to 16) the position of that word in the block; 16 means the block is used up.
+///
+/// ## Example
+/// ```
+/// use athena::rng_api::RngApi;
+/// use tyche::rngs::{SaveState, SeedableRng, Xoshiro256StarStar};
+///
+/// let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+/// rng.random_u64().unwrap();
+/// let checkpoint = rng.save_state();
+///
+/// let mut restored = Xoshiro256StarStar::from_state(&checkpoint).unwrap();
+/// assert_eq!(restored.random_u64().unwrap(), rng.random_u64().unwrap());
+/// ```
+pub trait SaveState: codec::Codec {
+    /// Encode the complete state of the generator.
+    fn save_state(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_LEN + Self::PAYLOAD_LEN + CHECKSUM_LEN);
+        out.extend_from_slice(&MAGIC);
+        out.push(VERSION);
+        out.push(Self::ID);
+        out.extend_from_slice(&(Self::PAYLOAD_LEN as u16).to_le_bytes());
+        self.write_payload(&mut out);
+        debug_assert_eq!(out.len(), HEADER_LEN + Self::PAYLOAD_LEN);
+        let checksum = crc32(&out);
+        out.extend_from_slice(&checksum.to_le_bytes());
+        out
+    }
+
+    /// Restore a generator from a state written by [`save_state`](SaveState::save_state).
+    ///
+    /// ## Errors
+    /// Returns `StateError` if `state` is not a valid, intact state of this generator.
+    fn from_state(state: &[u8]) -> Result<Self, StateError> {
+        if state.get(..4) != Some(&MAGIC[..]) {
+            return Err(StateError::BadMagic);
+        }
+        let version = *state.get(4).ok_or(StateError::BadLength)?;
+        if version != VERSION {
+            return Err(StateError::UnsupportedVersion(version));
+        }
+        if state.len() < HEADER_LEN + CHECKSUM_LEN {
+            return Err(StateError::BadLength);
+        }
+        let payload_len = u16::from_le_bytes([state[6], state[7]]) as usize;
+        if state.len() != HEADER_LEN + payload_len + CHECKSUM_LEN {
+            return Err(StateError::BadLength);
+        }
+        let (data, checksum) = state.split_at(HEADER_LEN + payload_len);
+        if crc32(data).to_le_bytes() != checksum {
+            return Err(StateError::BadChecksum);
+        }
+        if state[5] != Self::ID {
+            return Err(StateError::WrongGenerator(state[5]));
+        }
+        if payload_len != Self::PAYLOAD_LEN {
+            return Err(StateError::BadPayload);
+        }
+        Self::read_payload(&data[HEADER_LEN..]).ok_or(StateError::BadPayload)
+    }
+}
+
+impl<T: codec::Codec> SaveState for T {}
+
+/// CRC-32 with the IEEE polynomial, as used by zlib and PNG.
+pub(super) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Read the little endian `u64` at word `index` of `payload`.
+pub(super) fn read_u64(payload: &[u8], index: usize) -> u64 {
+    u64::from_le_bytes(payload[8 * index..8 * index + 8].try_into().unwrap())
+}
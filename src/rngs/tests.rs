@@ -0,0 +1,536 @@
+use super::*;
+use crate::{RngApiExt, Tyche};
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn keystream<const R: usize>(rng: &mut ChaChaRng<R>, len: usize) -> Vec<u8> {
+    let mut out = vec![0u8; len];
+    rng.fill_bytes(&mut out);
+    out
+}
+
+/// Key 00 01 02 .. 1f, as used in RFC 8439.
+fn counting_key() -> [u8; 32] {
+    std::array::from_fn(|i| i as u8)
+}
+
+#[test]
+fn test_chacha20_rfc8439_zero_key() {
+    // RFC 8439 appendix A.1, test vector 1: all zero key, nonce and counter
+    let mut rng = ChaCha20Rng::from_seed([0; 32]);
+    assert_eq!(
+        keystream(&mut rng, 64),
+        hex(
+            "76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7\
+             da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586"
+        )
+    );
+}
+
+#[test]
+fn test_chacha20_rfc8439_block_function() {
+    // RFC 8439 section 2.3.2: counter 1, nonce 00000009 0000004a 00000000. The 32 bit
+    // counter and first nonce word share our 64 bit counter, the rest is the stream id.
+    let mut rng = ChaCha20Rng::from_seed(counting_key());
+    rng.set_stream(0x4a00_0000);
+    rng.set_word_pos((1 | 0x0900_0000u128 << 32) * 16);
+    assert_eq!(
+        keystream(&mut rng, 64),
+        hex(
+            "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e\
+             d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e"
+        )
+    );
+}
+
+#[test]
+fn test_chacha_reduced_rounds() {
+    // First 32 bytes for the all zero key and nonce
+    let mut rng = ChaCha8Rng::from_seed([0; 32]);
+    assert_eq!(
+        keystream(&mut rng, 32),
+        hex("3e00ef2f895f40d67f5bb8e81f09a5a12c840ec3ce9a7f3b181be188ef711a1e")
+    );
+    let mut rng = ChaCha12Rng::from_seed([0; 32]);
+    assert_eq!(
+        keystream(&mut rng, 32),
+        hex("9bf49a6a0755f953811fce125f2683d50429c3bb49e074147e0089a52eae155f")
+    );
+}
+
+#[test]
+fn test_chacha_counter_is_64_bit() {
+    // Generated with the `cryptography` package, whose ChaCha20 takes all four state words
+    // 12 to 15 as the nonce. Blocks 2^32 - 1 and 2^32 of stream 7: the carry into word 13.
+    let mut rng = ChaCha20Rng::from_seed(counting_key());
+    rng.set_stream(7);
+    rng.set_word_pos(0xffff_ffff * 16);
+    assert_eq!(
+        keystream(&mut rng, 128),
+        hex(
+            "8c4d82733e527efd7db91ba9e07dd86625cac775a83298122c062c381eca3196\
+             24bcd7fe97767716bd962d0ecf9b375305769fa85ae7a5665ce2a912ffd53b75\
+             45dfd2131bbe5dedffd8e874ca22f73d87946449cce3e513e3fbe832ce622099\
+             507a1ec75ec46faf9551b9364d9641d528d785f2acb2161863155e4ba7e7a168"
+        )
+    );
+    // The last block of stream 3 and the wrap around to block 0
+    rng.set_stream(3);
+    rng.set_word_pos(((1 << 64) - 1) * 16);
+    assert_eq!(
+        keystream(&mut rng, 128),
+        hex(
+            "921c6a42fbcd5731b53bcbe51628ab5a3ddb00ec945c1cd7e782af64dd7f3c5a\
+             cb584f05f16c25f61c88d9ed50d1d1f53f3af3efc724126e7391623090c873e5\
+             697a41f898efe39ca1b05b75f501ec0cdf290f2c1a7949eae13700d6931a6ed5\
+             4fa9f0c08c58ec6898b9e9a2043ac1d553865088abcaa58b2161b51d6456b0aa"
+        )
+    );
+    assert_eq!(rng.get_word_pos(), 16);
+}
+
+#[test]
+fn test_chacha_word_pos_seeking() {
+    let mut rng = ChaCha20Rng::seed_from_u64(1);
+    let words = (0..100).map(|_| rng.next_u32()).collect::<Vec<u32>>();
+    assert_eq!(rng.get_word_pos(), 100);
+    for start in [0, 1, 15, 16, 17, 63, 99] {
+        let mut seek = ChaCha20Rng::seed_from_u64(1);
+        seek.set_word_pos(start as u128);
+        assert_eq!(seek.get_word_pos(), start as u128);
+        assert_eq!(seek.next_u32(), words[start]);
+    }
+    // A byte read consumes a whole word
+    let mut bytes = ChaCha20Rng::seed_from_u64(1);
+    assert_eq!(bytes.random_u8().unwrap(), words[0] as u8);
+    assert_eq!(bytes.get_word_pos(), 1);
+    assert_eq!(
+        bytes.random_u64().unwrap(),
+        words[1] as u64 | (words[2] as u64) << 32
+    );
+    bytes.set_word_pos(1 << 68);
+    assert_eq!(bytes.get_word_pos(), 0);
+}
+
+#[test]
+fn test_chacha_streams_are_independent() {
+    let mut a = ChaCha20Rng::seed_from_u64(5);
+    let mut b = ChaCha20Rng::seed_from_u64(5);
+    b.set_stream(1);
+    assert_eq!(b.get_stream(), 1);
+    assert_ne!(keystream(&mut a, 64), keystream(&mut b, 64));
+    // Switching streams keeps the position
+    let mut c = ChaCha20Rng::seed_from_u64(5);
+    c.set_word_pos(16);
+    c.set_stream(1);
+    assert_eq!(c.get_word_pos(), 16);
+    assert_eq!(keystream(&mut c, 64), keystream(&mut b, 64));
+}
+
+#[test]
+fn test_chacha_seeding() {
+    let seed = counting_key();
+    let rng = ChaCha8Rng::from_seed(seed);
+    assert_eq!(rng.get_seed(), seed);
+    assert_eq!(format!("{rng:?}"), "ChaChaRng<8> { .. }");
+
+    let mut a = ChaCha12Rng::seed_from_u64(7);
+    let mut b = ChaCha12Rng::seed_from_u64(7);
+    let mut c = ChaCha12Rng::seed_from_u64(8);
+    assert_eq!(a.get_seed(), b.get_seed());
+    assert_ne!(a.get_seed(), c.get_seed());
+    assert_eq!(a.next_u64(), b.next_u64());
+    assert_ne!(a.next_u64(), c.next_u64());
+
+    let mut tyche = Tyche::new().unwrap();
+    let x = ChaCha20Rng::from_rng(&mut tyche).unwrap();
+    let y = ChaCha20Rng::from_rng(&mut tyche).unwrap();
+    assert_ne!(x.get_seed(), y.get_seed());
+}
+
+#[test]
+fn test_chacha_drives_the_rng_api() {
+    let mut a = ChaCha20Rng::seed_from_u64(99);
+    let mut b = a.clone();
+    for _ in 0..100 {
+        assert_eq!(
+            a.random_from_range(0, 1000).unwrap(),
+            b.random_from_range(0, 1000).unwrap()
+        );
+        assert_eq!(
+            a.gen_range(-1.0..1.0).unwrap(),
+            b.gen_range(-1.0..1.0).unwrap()
+        );
+    }
+    let mut deck = (0..52).collect::<Vec<u32>>();
+    let mut copy = deck.clone();
+    a.shuffle(&mut deck).unwrap();
+    b.shuffle(&mut copy).unwrap();
+    assert_eq!(deck, copy);
+}
+
+#[test]
+fn test_chacha_output_is_uniform() {
+    let mut rng = ChaCha8Rng::seed_from_u64(2024);
+    let mut counts = [0u32; 256];
+    for byte in keystream(&mut rng, 256 * 4000) {
+        counts[byte as usize] += 1;
+    }
+    let stat = counts
+        .iter()
+        .map(|&c| (c as f64 - 4000.0).powi(2) / 4000.0)
+        .sum::<f64>();
+    assert!(stat < 377.08, "chi-square {stat}");
+}
+
+fn words_seed<const N: usize>(words: &[u64]) -> [u8; N] {
+    let width = N / words.len();
+    let mut seed = [0u8; N];
+    for (chunk, word) in seed.chunks_mut(width).zip(words) {
+        chunk.copy_from_slice(&word.to_le_bytes()[..width]);
+    }
+    seed
+}
+
+#[test]
+fn test_splitmix64_reference_vectors() {
+    let mut rng = SplitMix64::seed_from_u64(1234567);
+    let expected = [
+        6457827717110365317,
+        3203168211198807973,
+        9817491932198370423,
+        4593380528125082431,
+        16408922859458223821,
+    ];
+    for value in expected {
+        assert_eq!(rng.next_u64(), value);
+    }
+    assert_eq!(
+        SplitMix64::from_seed(1234567u64.to_le_bytes()),
+        SplitMix64::seed_from_u64(1234567)
+    );
+}
+
+#[test]
+fn test_xoshiro256_reference_vectors() {
+    let mut rng = Xoshiro256StarStar::from_seed(words_seed(&[1, 2, 3, 4]));
+    let expected = [
+        11520,
+        0,
+        1509978240,
+        1215971899390074240,
+        1216172134540287360,
+        607988272756665600,
+        16172922978634559625,
+        8476171486693032832,
+        10595114339597558777,
+        2904607092377533576,
+    ];
+    for value in expected {
+        assert_eq!(rng.next_u64(), value);
+    }
+
+    let mut jumped = Xoshiro256StarStar::from_seed(words_seed(&[1, 2, 3, 4]));
+    jumped.jump();
+    let outputs = [jumped.next_u64(), jumped.next_u64(), jumped.next_u64()];
+    assert_eq!(
+        outputs,
+        [0xbbd2f312298443d8, 0x62e57db2d5706577, 0x34d1890374a6d72b]
+    );
+
+    let mut jumped = Xoshiro256StarStar::from_seed(words_seed(&[1, 2, 3, 4]));
+    jumped.long_jump();
+    let outputs = [jumped.next_u64(), jumped.next_u64(), jumped.next_u64()];
+    assert_eq!(
+        outputs,
+        [0x527752a1d792704d, 0xd8d8bdec57599e64, 0x601cb926727eb003]
+    );
+}
+
+#[test]
+fn test_xoshiro128_reference_vectors() {
+    let mut rng = Xoshiro128PlusPlus::from_seed(words_seed(&[1, 2, 3, 4]));
+    let expected = [
+        641, 1573767, 3222811527, 3517856514, 836907274, 4247214768, 3867114732, 1355841295,
+        495546011, 621204420,
+    ];
+    for value in expected {
+        assert_eq!(rng.next_u32(), value);
+    }
+
+    let mut jumped = Xoshiro128PlusPlus::from_seed(words_seed(&[1, 2, 3, 4]));
+    jumped.jump();
+    let outputs = [jumped.next_u32(), jumped.next_u32(), jumped.next_u32()];
+    assert_eq!(outputs, [0xba8c0ddc, 0x6a228ce, 0x4506c342]);
+
+    let mut jumped = Xoshiro128PlusPlus::from_seed(words_seed(&[1, 2, 3, 4]));
+    jumped.long_jump();
+    let outputs = [jumped.next_u32(), jumped.next_u32(), jumped.next_u32()];
+    assert_eq!(outputs, [0x99cc2935, 0x7f4f19b6, 0x9b914e1]);
+}
+
+#[test]
+fn test_xoshiro_zero_seed_is_replaced() {
+    let mut a = Xoshiro256StarStar::from_seed([0; 32]);
+    assert_eq!(a, Xoshiro256StarStar::seed_from_u64(0));
+    assert_ne!(a.next_u64() | a.next_u64(), 0);
+
+    let mut b = Xoshiro128PlusPlus::from_seed([0; 16]);
+    assert_eq!(b, Xoshiro128PlusPlus::seed_from_u64(0));
+    assert_ne!(b.next_u32() | b.next_u32(), 0);
+}
+
+#[test]
+fn test_pcg32_reference_vectors() {
+    let mut rng = Pcg32::new(42, 54);
+    let expected = [
+        0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e,
+    ];
+    for value in expected {
+        assert_eq!(rng.next_u32(), value);
+    }
+    assert_eq!(Pcg32::from_seed(words_seed(&[42, 54])), Pcg32::new(42, 54));
+
+    let mut stepped = Pcg32::new(42, 54);
+    for _ in 0..1000 {
+        stepped.next_u32();
+    }
+    let mut advanced = Pcg32::new(42, 54);
+    advanced.advance(1000);
+    assert_eq!(advanced, stepped);
+    assert_eq!(advanced.next_u32(), 0xefebeab3);
+
+    // A full period brings the generator back to the start
+    let mut wrapped = Pcg32::new(42, 54);
+    wrapped.advance(u64::MAX);
+    wrapped.next_u32();
+    assert_eq!(wrapped, Pcg32::new(42, 54));
+}
+
+#[test]
+fn test_pcg64_reference_vectors() {
+    let mut rng = Pcg64::new(42, 54);
+    let expected = [
+        0x86b1da1d72062b68,
+        0x1304aa46c9853d39,
+        0xa3670e9e0dd50358,
+        0xf9090e529a7dae00,
+        0xc85b9fd837996f2c,
+        0x606121f8e3919196,
+    ];
+    for value in expected {
+        assert_eq!(rng.next_u64(), value);
+    }
+    assert_eq!(
+        Pcg64::from_seed(words_seed(&[42, 0, 54, 0])),
+        Pcg64::new(42, 54)
+    );
+
+    let mut stepped = Pcg64::new(42, 54);
+    for _ in 0..1000 {
+        stepped.next_u64();
+    }
+    let mut advanced = Pcg64::new(42, 54);
+    advanced.advance(1000);
+    assert_eq!(advanced, stepped);
+    assert_eq!(advanced.next_u64(), 0xf771891bd1a77d13);
+}
+
+#[test]
+fn test_splitmix64_advance_matches_stepping() {
+    let mut stepped = SplitMix64::seed_from_u64(5);
+    for _ in 0..1000 {
+        stepped.next_u64();
+    }
+    let mut advanced = SplitMix64::seed_from_u64(5);
+    advanced.advance(1000);
+    assert_eq!(advanced, stepped);
+}
+
+#[test]
+fn test_fast_rngs_fill_little_endian_words() {
+    let mut a = Xoshiro256StarStar::seed_from_u64(3);
+    let mut b = a.clone();
+    let mut bytes = [0u8; 20];
+    a.fill_bytes(&mut bytes);
+    assert_eq!(bytes[..8], b.next_u64().to_le_bytes());
+    assert_eq!(bytes[8..16], b.next_u64().to_le_bytes());
+    assert_eq!(bytes[16..], b.next_u64().to_le_bytes()[..4]);
+    assert_eq!(a, b);
+
+    let mut c = Pcg32::seed_from_u64(3);
+    let mut d = c.clone();
+    let mut bytes = [0u8; 6];
+    c.fill_bytes(&mut bytes);
+    assert_eq!(bytes[..4], d.next_u32().to_le_bytes());
+    assert_eq!(bytes[4..], d.next_u32().to_le_bytes()[..2]);
+    assert_eq!(c, d);
+}
+
+#[test]
+fn test_fast_rngs_drive_the_rng_api() {
+    fn check<R: RngApi + SeedableRng + Clone>() {
+        let mut tyche = Tyche::new().unwrap();
+        let mut a = R::from_rng(&mut tyche).unwrap();
+        let mut b = a.clone();
+        for _ in 0..100 {
+            assert_eq!(a.random_u64().unwrap(), b.random_u64().unwrap());
+            assert_eq!(
+                a.gen_range(0..1000u32).unwrap(),
+                b.gen_range(0..1000u32).unwrap()
+            );
+        }
+        let mut counts = [0u32; 256];
+        for byte in a.random_bytes(256 * 4000).unwrap() {
+            counts[byte as usize] += 1;
+        }
+        let stat = counts
+            .iter()
+            .map(|&c| (c as f64 - 4000.0).powi(2) / 4000.0)
+            .sum::<f64>();
+        assert!(stat < 377.08, "chi-square {stat}");
+    }
+    check::<SplitMix64>();
+    check::<Xoshiro256StarStar>();
+    check::<Xoshiro128PlusPlus>();
+    check::<Pcg32>();
+    check::<Pcg64>();
+}
+
+/// Save `rng` after `skip` outputs, restore it and check both continue identically.
+fn assert_round_trip<R: SaveState + RngApi + PartialEq + std::fmt::Debug>(mut rng: R, skip: usize) {
+    for _ in 0..skip {
+        rng.random_u32().unwrap();
+    }
+    let state = rng.save_state();
+    let mut restored = R::from_state(&state).unwrap();
+    assert_eq!(restored, rng);
+    assert_eq!(restored.save_state(), state);
+    for _ in 0..100 {
+        assert_eq!(restored.random_u64().unwrap(), rng.random_u64().unwrap());
+    }
+}
+
+#[test]
+fn test_saved_state_round_trips() {
+    for skip in [0, 1, 5, 15, 16, 17, 1000] {
+        assert_round_trip(ChaCha8Rng::seed_from_u64(1), skip);
+        assert_round_trip(ChaCha12Rng::seed_from_u64(2), skip);
+        assert_round_trip(ChaCha20Rng::seed_from_u64(3), skip);
+        assert_round_trip(Xoshiro256StarStar::seed_from_u64(4), skip);
+        assert_round_trip(Xoshiro128PlusPlus::seed_from_u64(5), skip);
+        assert_round_trip(Pcg32::seed_from_u64(6), skip);
+        assert_round_trip(Pcg64::seed_from_u64(7), skip);
+        assert_round_trip(SplitMix64::seed_from_u64(8), skip);
+    }
+
+    // Stream and position of ChaCha are part of the state
+    let mut rng = ChaCha20Rng::from_seed(counting_key());
+    rng.set_stream(0xdead_beef);
+    rng.set_word_pos(u64::MAX as u128 * 16 + 13);
+    let restored = ChaCha20Rng::from_state(&rng.save_state()).unwrap();
+    assert_eq!(restored.get_stream(), 0xdead_beef);
+    assert_eq!(restored.get_word_pos(), rng.get_word_pos());
+    assert_round_trip(rng, 7);
+}
+
+#[test]
+fn test_saved_state_format() {
+    assert_eq!(super::state::crc32(b"123456789"), 0xcbf4_3926);
+
+    let state = SplitMix64::seed_from_u64(0x0123_4567_89ab_cdef).save_state();
+    assert_eq!(
+        state,
+        [
+            b'T', b'Y', b'C', b'H', 1, 8, 8, 0, 0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01,
+            0xf7, 0x7c, 0xc6, 0xc1
+        ]
+    );
+
+    let lengths = [
+        ChaCha8Rng::seed_from_u64(0).save_state().len(),
+        Xoshiro256StarStar::seed_from_u64(0).save_state().len(),
+        Xoshiro128PlusPlus::seed_from_u64(0).save_state().len(),
+        Pcg32::seed_from_u64(0).save_state().len(),
+        Pcg64::seed_from_u64(0).save_state().len(),
+    ];
+    assert_eq!(lengths, [61, 44, 28, 28, 44]);
+    let ids = [
+        ChaCha8Rng::seed_from_u64(0).save_state()[5],
+        ChaCha12Rng::seed_from_u64(0).save_state()[5],
+        ChaCha20Rng::seed_from_u64(0).save_state()[5],
+    ];
+    assert_eq!(ids, [1, 2, 3]);
+}
+
+/// Replace the payload of `state` and fix up the checksum, to forge valid looking states.
+fn with_payload(state: &[u8], payload: &[u8]) -> Vec<u8> {
+    let mut forged = state[..8].to_vec();
+    forged.extend_from_slice(payload);
+    let checksum = super::state::crc32(&forged);
+    forged.extend_from_slice(&checksum.to_le_bytes());
+    forged
+}
+
+#[test]
+fn test_saved_state_errors() {
+    let state = Pcg32::new(42, 54).save_state();
+
+    assert_eq!(Pcg32::from_state(&[]), Err(StateError::BadMagic));
+    assert_eq!(Pcg32::from_state(b"PNG\x0d\x0a"), Err(StateError::BadMagic));
+    // A truncated state is still recognised as one
+    assert_eq!(Pcg32::from_state(b"TYCH"), Err(StateError::BadLength));
+    assert_eq!(Pcg32::from_state(&state[..6]), Err(StateError::BadLength));
+    let mut future = state.clone();
+    future[4] = 2;
+    assert_eq!(
+        Pcg32::from_state(&future),
+        Err(StateError::UnsupportedVersion(2))
+    );
+    assert_eq!(
+        Pcg32::from_state(&state[..state.len() - 1]),
+        Err(StateError::BadLength)
+    );
+    let mut longer = state.clone();
+    longer.push(0);
+    assert_eq!(Pcg32::from_state(&longer), Err(StateError::BadLength));
+    for i in 8..state.len() {
+        let mut corrupted = state.clone();
+        corrupted[i] ^= 0x10;
+        assert_eq!(Pcg32::from_state(&corrupted), Err(StateError::BadChecksum));
+    }
+    assert_eq!(
+        Pcg64::from_state(&state),
+        Err(StateError::WrongGenerator(6))
+    );
+    assert_eq!(
+        Xoshiro128PlusPlus::from_state(&state),
+        Err(StateError::WrongGenerator(6))
+    );
+
+    // Intact, but impossible states
+    let even = with_payload(&state, &[1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(Pcg32::from_state(&even), Err(StateError::BadPayload));
+    let zero = Xoshiro256StarStar::seed_from_u64(1).save_state();
+    let zero = with_payload(&zero, &[0; 32]);
+    assert_eq!(
+        Xoshiro256StarStar::from_state(&zero),
+        Err(StateError::BadPayload)
+    );
+    let mut chacha = ChaCha20Rng::seed_from_u64(1).save_state();
+    chacha[8 + 48] = 17;
+    let chacha = with_payload(&chacha, &chacha[8..8 + 49]);
+    assert_eq!(
+        ChaCha20Rng::from_state(&chacha),
+        Err(StateError::BadPayload)
+    );
+    assert_eq!(
+        StateError::BadChecksum.to_string(),
+        "The saved state is corrupted!"
+    );
+}
@@ -0,0 +1,230 @@
+//! The xoshiro generators by Blackman & Vigna.
+//!
+//! See "Scrambled Linear Pseudorandom Number Generators", 2021, and <https://prng.di.unimi.it>.
+
+use super::state::codec::Codec;
+use super::state::read_u64;
+use super::{SeedableRng, fill_via_u32, fill_via_u64};
+use crate::macros::rng_api_methods;
+use athena::rng_api::{RngApi, RngResult};
+
+/// Xoshiro256** (xor, shift, rotate with a `*5, <<< 7, *9` scrambler), 64 bit output.
+///
+/// **Not cryptographically secure**, its output is easily predicted from a few values. A fast
+/// all-purpose generator with 256 bits of state and a period of `2^256 - 1`.
+/// [`jump`](Self::jump) and [`long_jump`](Self::long_jump) split the period into
+/// non-overlapping sequences for parallel use.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Xoshiro256StarStar {
+    s: [u64; 4],
+}
+
+impl Xoshiro256StarStar {
+    /// The next 64 bits.
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.s;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// Fill `buffer` with output in little endian byte order.
+    ///
+    /// A trailing partial word discards its unused bytes.
+    pub fn fill_bytes(&mut self, buffer: &mut [u8]) {
+        fill_via_u64(buffer, || self.next_u64());
+    }
+
+    /// Advance by `2^128` outputs, giving `2^128` non-overlapping sequences of `2^128` values.
+    pub fn jump(&mut self) {
+        self.apply_jump([
+            0x180e_c6d3_3cfd_0aba,
+            0xd5a6_1266_f0c9_392c,
+            0xa958_2618_e03f_c9aa,
+            0x39ab_dc45_29b1_661c,
+        ]);
+    }
+
+    /// Advance by `2^192` outputs, giving `2^64` starting points from each of which
+    /// [`jump`](Self::jump) yields `2^64` sequences.
+    pub fn long_jump(&mut self) {
+        self.apply_jump([
+            0x76e1_5d3e_fefd_cbbf,
+            0xc500_4e44_1c52_2fb3,
+            0x7771_0069_854e_e241,
+            0x3910_9bb0_2acb_e635,
+        ]);
+    }
+
+    /// Multiply the state by the jump polynomial, `x^distance` modulo the characteristic
+    /// polynomial of the state transition.
+    fn apply_jump(&mut self, polynomial: [u64; 4]) {
+        let mut jumped = [0u64; 4];
+        for word in polynomial {
+            for bit in 0..64 {
+                if word & 1 << bit != 0 {
+                    jumped.iter_mut().zip(self.s).for_each(|(j, s)| *j ^= s);
+                }
+                self.next_u64();
+            }
+        }
+        self.s = jumped;
+    }
+
+    fn fill(&mut self, buffer: &mut [u8]) -> RngResult<()> {
+        self.fill_bytes(buffer);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Xoshiro256StarStar {
+    type Seed = [u8; 32];
+
+    /// Use `seed` as the state; the all zero seed, a fixed point, is replaced by
+    /// `seed_from_u64(0)`.
+    fn from_seed(seed: [u8; 32]) -> Self {
+        if seed == [0; 32] {
+            return Self::seed_from_u64(0);
+        }
+        let mut s = [0u64; 4];
+        for (word, chunk) in s.iter_mut().zip(seed.chunks_exact(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        Xoshiro256StarStar { s }
+    }
+}
+
+impl RngApi for Xoshiro256StarStar {
+    rng_api_methods!();
+}
+
+impl Codec for Xoshiro256StarStar {
+    const ID: u8 = 4;
+    const PAYLOAD_LEN: usize = 32;
+
+    fn write_payload(&self, out: &mut Vec<u8>) {
+        for word in self.s {
+            out.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+
+    /// The all zero state is a fixed point and rejected.
+    fn read_payload(payload: &[u8]) -> Option<Self> {
+        let s = std::array::from_fn(|i| read_u64(payload, i));
+        (s != [0; 4]).then_some(Xoshiro256StarStar { s })
+    }
+}
+
+/// Xoshiro128++ (xor, shift, rotate with a `+ <<< 7 +` scrambler), 32 bit output.
+///
+/// **Not cryptographically secure.** The 32 bit sibling of [`Xoshiro256StarStar`], with 128
+/// bits of state and a period of `2^128 - 1`; fastest where 64 bit multiplication is slow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Xoshiro128PlusPlus {
+    s: [u32; 4],
+}
+
+impl Xoshiro128PlusPlus {
+    /// The next 32 bits.
+    pub fn next_u32(&mut self) -> u32 {
+        let s = &mut self.s;
+        let result = s[0].wrapping_add(s[3]).rotate_left(7).wrapping_add(s[0]);
+        let t = s[1] << 9;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(11);
+        result
+    }
+
+    /// The next two outputs, the first as the low half.
+    pub fn next_u64(&mut self) -> u64 {
+        let low = self.next_u32() as u64;
+        low | (self.next_u32() as u64) << 32
+    }
+
+    /// Fill `buffer` with output in little endian byte order.
+    ///
+    /// A trailing partial word discards its unused bytes.
+    pub fn fill_bytes(&mut self, buffer: &mut [u8]) {
+        fill_via_u32(buffer, || self.next_u32());
+    }
+
+    /// Advance by `2^64` outputs, giving `2^64` non-overlapping sequences of `2^64` values.
+    pub fn jump(&mut self) {
+        self.apply_jump([0x8764_000b, 0xf542_d2d3, 0x6fa0_35c3, 0x77f2_db5b]);
+    }
+
+    /// Advance by `2^96` outputs, giving `2^32` starting points from each of which
+    /// [`jump`](Self::jump) yields `2^32` sequences.
+    pub fn long_jump(&mut self) {
+        self.apply_jump([0xb523_952e, 0x0b6f_099f, 0xccf5_a0ef, 0x1c58_0662]);
+    }
+
+    /// Multiply the state by the jump polynomial, see [`Xoshiro256StarStar::jump`].
+    fn apply_jump(&mut self, polynomial: [u32; 4]) {
+        let mut jumped = [0u32; 4];
+        for word in polynomial {
+            for bit in 0..32 {
+                if word & 1 << bit != 0 {
+                    jumped.iter_mut().zip(self.s).for_each(|(j, s)| *j ^= s);
+                }
+                self.next_u32();
+            }
+        }
+        self.s = jumped;
+    }
+
+    fn fill(&mut self, buffer: &mut [u8]) -> RngResult<()> {
+        self.fill_bytes(buffer);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Xoshiro128PlusPlus {
+    type Seed = [u8; 16];
+
+    /// Use `seed` as the state; the all zero seed, a fixed point, is replaced by
+    /// `seed_from_u64(0)`.
+    fn from_seed(seed: [u8; 16]) -> Self {
+        if seed == [0; 16] {
+            return Self::seed_from_u64(0);
+        }
+        let mut s = [0u32; 4];
+        for (word, chunk) in s.iter_mut().zip(seed.chunks_exact(4)) {
+            *word = u32::from_le_bytes(chunk.try_into().unwrap());
+        }
+        Xoshiro128PlusPlus { s }
+    }
+}
+
+impl RngApi for Xoshiro128PlusPlus {
+    rng_api_methods!();
+}
+
+impl Codec for Xoshiro128PlusPlus {
+    const ID: u8 = 5;
+    const PAYLOAD_LEN: usize = 16;
+
+    fn write_payload(&self, out: &mut Vec<u8>) {
+        for word in self.s {
+            out.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+
+    /// The all zero state is a fixed point and rejected.
+    fn read_payload(payload: &[u8]) -> Option<Self> {
+        let s = std::array::from_fn(|i| {
+            u32::from_le_bytes(payload[4 * i..4 * i + 4].try_into().unwrap())
+        });
+        (s != [0; 4]).then_some(Xoshiro128PlusPlus { s })
+    }
+}
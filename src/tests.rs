@@ -1,4 +1,9 @@
 use crate::prelude::*;
+use crate::{
+    EntropySource, IndexVec, ReaderSource, ReseedingRng, RngApiExt, Tyche, WeightError,
+    WeightedIndex, os,
+};
+use athena::rng_api::RngApi;
 // Run with `cargo test -- --nocapture` for printout
 
 #[test]
@@ -177,13 +182,14 @@ fn test_random_f32range() {
     for _ in 0..1500000 {
         let answ = random_from_f32range(0.1, 100.1).unwrap();
         //println!("{:?}", answ);
-        if !found0_1 && answ == 0.1 {
+        // The range is uniform, so hitting 0.1 exactly has a chance of 2^-24 per draw
+        if !found0_1 && answ < 0.11 {
             found0_1 = true;
         }
         if !found100_1 && answ > 100.09 {
             found100_1 = true;
         }
-        assert!(answ >= 0.1 && answ <= 100.1);
+        assert!((0.1..100.1).contains(&answ));
         //tmpvec.push(answ.unwrap());
     }
     //println!("{:?}", tmpvec.into_iter().reduce(f32::max).unwrap());
@@ -247,3 +253,940 @@ fn test_failure_states() {
     let index = random_index(0);
     assert!(index.is_err());
 }
+
+#[test]
+fn test_getrandom_backend() {
+    let mut tyche = Tyche::new().unwrap();
+    for _ in 0..1500 {
+        assert!(tyche.random_u64().is_ok());
+    }
+    // Large requests are split into several syscalls
+    let bytes = tyche.random_bytes(1 << 20).unwrap();
+    assert_eq!(bytes.len(), 1 << 20);
+    assert!(bytes.iter().any(|b| *b != 0));
+}
+
+#[test]
+fn test_getrandom_unsupported() {
+    // Targets without a known syscall number must look like an `ENOSYS` kernel, so the
+    // probe reports `getrandom` as missing and `GetRandom` falls back to `/dev/urandom`.
+    assert!(!os::probe(os::unsupported::getrandom));
+    let err = os::fill_with(os::unsupported::getrandom, &mut [0u8; 16]).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(os::ENOSYS));
+    assert!(os::fill_with(os::unsupported::getrandom, &mut []).is_ok());
+
+    assert_eq!(os::probe(os::sys::getrandom), os::getrandom_available());
+}
+
+#[test]
+fn test_urandom_backend() {
+    let mut tyche = Tyche::from_urandom().unwrap();
+    for _ in 0..1500 {
+        assert!(tyche.random_u64().is_ok());
+    }
+    assert_eq!(tyche.random_bytes(4096).unwrap().len(), 4096);
+}
+
+#[test]
+fn test_reader_source() {
+    let mut tyche = Tyche::from_reader(std::io::repeat(0xAB));
+    assert_eq!(tyche.random_u16().unwrap(), 0xABAB);
+    assert_eq!(tyche.random_u64().unwrap(), 0xABAB_ABAB_ABAB_ABAB);
+
+    // A reader that runs dry reports an error instead of short output
+    let mut tyche = Tyche::from_reader(&[1u8, 2][..]);
+    assert_eq!(tyche.random_u8().unwrap(), 1);
+    assert!(tyche.random_u32().is_err());
+}
+
+struct Counter(u8);
+
+impl EntropySource for Counter {
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), std::io::Error> {
+        for byte in buffer {
+            *byte = self.0;
+            self.0 = self.0.wrapping_add(1);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_custom_source() {
+    let mut tyche = Tyche::with_source(Counter(0));
+    assert_eq!(tyche.random_u32().unwrap(), u32::from_le_bytes([0, 1, 2, 3]));
+    assert_eq!(tyche.random_bytes(3).unwrap(), vec![4, 5, 6]);
+    assert_eq!(tyche.into_source().0, 7);
+}
+
+#[test]
+fn test_buffered_source() {
+    let mut tyche = Tyche::with_source(Counter(0)).with_buffer_size(16);
+    assert_eq!(tyche.buffer_size(), 16);
+    // The first small request reads the whole buffer ahead, but hands bytes out in order
+    assert_eq!(tyche.random_u8().unwrap(), 0);
+    assert_eq!(tyche.random_u32().unwrap(), u32::from_le_bytes([1, 2, 3, 4]));
+    // Requests spanning a refill continue the stream seamlessly
+    assert_eq!(tyche.random_u64().unwrap(), u64::from_le_bytes([5, 6, 7, 8, 9, 10, 11, 12]));
+    assert_eq!(tyche.random_u64().unwrap(), u64::from_le_bytes([13, 14, 15, 16, 17, 18, 19, 20]));
+    // Requests as large as the buffer bypass it
+    assert_eq!(tyche.random_bytes(16).unwrap(), (32..48).collect::<Vec<u8>>());
+    assert_eq!(tyche.random_u8().unwrap(), 21);
+    assert_eq!(tyche.into_source().0, 48);
+}
+
+#[test]
+fn test_buffered_reader_error() {
+    let mut tyche = Tyche::from_reader(&[1u8, 2, 3][..]).with_buffer_size(8);
+    assert!(tyche.random_u8().is_err());
+    let mut tyche = Tyche::from_reader(&[1u8; 8][..]).with_buffer_size(8);
+    assert_eq!(tyche.random_u32().unwrap(), 0x0101_0101);
+    assert_eq!(tyche.random_u32().unwrap(), 0x0101_0101);
+    assert!(tyche.random_u8().is_err());
+}
+
+#[test]
+fn test_default_buffer() {
+    let mut tyche = Tyche::new().unwrap();
+    assert!(tyche.buffer_size() > 0);
+    for _ in 0..1500000 {
+        assert!(tyche.random_u8().is_ok());
+    }
+    let mut tyche = Tyche::new().unwrap().with_buffer_size(0);
+    assert_eq!(tyche.buffer_size(), 0);
+    assert!(tyche.random_u64().is_ok());
+}
+
+#[test]
+fn test_thread_rng() {
+    let mut a = crate::thread_rng();
+    let mut b = crate::thread_rng();
+    for _ in 0..1500 {
+        assert!(a.random_u32().is_ok());
+        assert!(b.random_from_range(0, 10).unwrap() < 10);
+    }
+    // Every thread lazily creates its own instance
+    let handles = (0..4)
+        .map(|_| std::thread::spawn(|| crate::thread_rng().random_u64().is_ok()))
+        .collect::<Vec<_>>();
+    for handle in handles {
+        assert!(handle.join().unwrap());
+    }
+}
+
+/// The ChaCha20 keystream of `key`, as consumed by one `ReseedingRng` refill.
+fn chacha20_batch(key: [u8; 32]) -> Vec<u8> {
+    use crate::rngs::{ChaCha20Rng, SeedableRng};
+    let mut batch = vec![0u8; 768];
+    ChaCha20Rng::from_seed(key).fill_bytes(&mut batch);
+    batch
+}
+
+#[test]
+fn test_reseeding_rng_fast_key_erasure() {
+    let mut rng = ReseedingRng::with_source(Counter(0)).unwrap();
+    // The seed is the first 32 source bytes; each batch hands out all but the first 32 bytes,
+    // which become the next key
+    let first = chacha20_batch(std::array::from_fn(|i| i as u8));
+    let second = chacha20_batch(first[..32].try_into().unwrap());
+    assert_eq!(rng.random_bytes(736).unwrap(), first[32..]);
+    assert_eq!(rng.random_bytes(100).unwrap(), second[32..132]);
+    assert_eq!(rng.into_source().0, 32);
+}
+
+#[test]
+fn test_reseeding_rng_reseeds() {
+    // By output volume: checked at every refill of 736 bytes
+    let mut rng = ReseedingRng::with_source(Counter(0)).unwrap().with_reseed_bytes(1000);
+    assert_eq!(rng.reseed_bytes(), 1000);
+    let first = chacha20_batch(std::array::from_fn(|i| i as u8));
+    let second = chacha20_batch(first[..32].try_into().unwrap());
+    rng.random_bytes(2 * 736).unwrap();
+    let key: [u8; 32] = std::array::from_fn(|i| second[i] ^ (32 + i as u8));
+    assert_eq!(rng.random_bytes(736).unwrap(), chacha20_batch(key)[32..]);
+    assert_eq!(rng.into_source().0, 64);
+
+    // By time: a zero interval reseeds before every refill
+    let mut rng = ReseedingRng::with_source(Counter(0))
+        .unwrap()
+        .with_reseed_interval(std::time::Duration::ZERO);
+    rng.random_bytes(3 * 736).unwrap();
+    assert_eq!(rng.into_source().0, 128);
+
+    // Explicitly, discarding buffered output
+    let mut rng = ReseedingRng::with_source(Counter(0)).unwrap();
+    rng.random_u8().unwrap();
+    rng.reseed().unwrap();
+    let key: [u8; 32] = std::array::from_fn(|i| first[i] ^ (32 + i as u8));
+    assert_eq!(rng.random_bytes(8).unwrap(), chacha20_batch(key)[32..40]);
+}
+
+#[test]
+fn test_reseeding_rng_source_errors() {
+    assert!(ReseedingRng::with_source(ReaderSource::new(&[1u8; 31][..])).is_err());
+    let mut rng = ReseedingRng::with_source(ReaderSource::new(&[1u8; 40][..]))
+        .unwrap()
+        .with_reseed_interval(std::time::Duration::ZERO);
+    assert!(rng.random_u8().is_err());
+}
+
+#[test]
+fn test_reseeding_rng() {
+    let mut a = ReseedingRng::new().unwrap();
+    let mut b = ReseedingRng::new().unwrap();
+    assert_eq!(a.reseed_bytes(), ReseedingRng::<crate::GetRandom>::DEFAULT_RESEED_BYTES);
+    assert_ne!(a.random_bytes(32).unwrap(), b.random_bytes(32).unwrap());
+    let mut counts = [0usize; 256];
+    for byte in a.random_bytes(1 << 21).unwrap() {
+        counts[byte as usize] += 1;
+    }
+    assert!(chi_square(&counts) < 377.08);
+    assert!(!format!("{a:?}").contains("key"));
+}
+
+/// Run `draw` in a forked child and return its output, sent back through a pipe.
+#[cfg(unix)]
+fn in_child(draw: impl FnOnce() -> [u64; 4]) -> [u64; 4] {
+    use std::ffi::{c_int, c_void};
+
+    unsafe extern "C" {
+        fn fork() -> c_int;
+        fn pipe(fds: *mut c_int) -> c_int;
+        fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+        fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+        fn close(fd: c_int) -> c_int;
+        fn waitpid(pid: c_int, status: *mut c_int, options: c_int) -> c_int;
+        fn _exit(status: c_int) -> !;
+    }
+
+    let mut fds = [0 as c_int; 2];
+    let mut output = [0u64; 4];
+    // SAFETY: plain libc calls on valid pointers; the child only generates numbers, writes
+    // them to the pipe and exits without running any destructor of the test harness.
+    unsafe {
+        assert_eq!(pipe(fds.as_mut_ptr()), 0);
+        let pid = fork();
+        assert!(pid >= 0);
+        if pid == 0 {
+            let values = draw();
+            write(fds[1], values.as_ptr().cast(), 32);
+            _exit(0);
+        }
+        close(fds[1]);
+        assert_eq!(read(fds[0], output.as_mut_ptr().cast(), 32), 32);
+        close(fds[0]);
+        let mut status = 0;
+        assert_eq!(waitpid(pid, &mut status, 0), pid);
+        assert_eq!(status, 0);
+    }
+    output
+}
+
+/// Fill the buffers of `rng`, fork, and check that parent and child continue differently.
+#[cfg(unix)]
+fn assert_fork_safe<R: RngApi>(mut rng: R) {
+    rng.random_u8().unwrap();
+    let draw = |rng: &mut R| std::array::from_fn(|_| rng.random_u64().unwrap());
+    let child = in_child(|| draw(&mut rng));
+    assert_ne!(child, draw(&mut rng));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_fork_safety() {
+    use crate::drbg::{CtrDrbg, Drbg, HashDrbg, HmacDrbg};
+    use crate::rngs::{ChaCha20Rng, SeedableRng};
+
+    assert_fork_safe(Tyche::new().unwrap());
+    assert_fork_safe(Tyche::with_source(crate::GetRandom::new().unwrap()).with_buffer_size(4096));
+    assert_fork_safe(crate::thread_rng());
+    assert_fork_safe(ReseedingRng::new().unwrap());
+    assert_fork_safe(HashDrbg::from_entropy(&[]).unwrap());
+    assert_fork_safe(HmacDrbg::from_entropy(&[]).unwrap());
+    assert_fork_safe(CtrDrbg::from_entropy(&[]).unwrap());
+
+    // Seedable generators are deterministic on purpose and replay in the child
+    let rng = ChaCha20Rng::seed_from_u64(7);
+    let mut copy = rng.clone();
+    let child = in_child(move || {
+        let mut rng = rng;
+        std::array::from_fn(|_| rng.random_u64().unwrap())
+    });
+    assert_eq!(child, std::array::from_fn(|_| copy.random_u64().unwrap()));
+}
+
+/// Pearson's chi-square statistic of `counts` against a uniform expectation.
+fn chi_square(counts: &[usize]) -> f64 {
+    let total: usize = counts.iter().sum();
+    let expected = total as f64 / counts.len() as f64;
+    counts
+        .iter()
+        .map(|&c| (c as f64 - expected).powi(2) / expected)
+        .sum()
+}
+
+/// Fraction of 100000 draws for which `below_half` reports a result in the lower half of its range.
+fn fraction_below_half<F: FnMut() -> bool>(mut below_half: F) -> f64 {
+    let hits = (0..100000).filter(|_| below_half()).count();
+    hits as f64 / 100000.0
+}
+
+#[test]
+fn test_range_modulo_bias() {
+    // For a range of 0.4 * 2^w, reducing a w-bit word with `%` maps three words onto each
+    // result in the lower half of the range and only two onto the upper half, so the lower
+    // half shows up 60% of the time instead of 50%.
+    let mut tyche = Tyche::new().unwrap();
+
+    let n = u64::MAX / 5 * 2;
+    let frac = fraction_below_half(|| tyche.random_from_u64_range(0, n).unwrap() < n / 2);
+    assert!((frac - 0.5).abs() < 0.02, "u64 range is biased: {frac}");
+
+    let n = usize::MAX / 5 * 2;
+    let frac = fraction_below_half(|| tyche.random_from_range(0, n).unwrap() < n / 2);
+    assert!((frac - 0.5).abs() < 0.02, "usize range is biased: {frac}");
+
+    let frac = fraction_below_half(|| tyche.random_from_range_inclusive(0, n).unwrap() < n / 2);
+    assert!((frac - 0.5).abs() < 0.02, "inclusive usize range is biased: {frac}");
+
+    let n = (u64::MAX / 5 * 2) as i64;
+    let frac = fraction_below_half(|| tyche.random_from_i64_range(0, n).unwrap() < n / 2);
+    assert!((frac - 0.5).abs() < 0.02, "i64 range is biased: {frac}");
+
+    let n = (usize::MAX / 5 * 2) as isize;
+    let frac = fraction_below_half(|| tyche.random_from_i_range(0, n).unwrap() < n / 2);
+    assert!((frac - 0.5).abs() < 0.02, "isize range is biased: {frac}");
+
+    let n = (u32::MAX / 5 * 2) as i32;
+    let frac = fraction_below_half(|| tyche.random_from_i32_range(0, n).unwrap() < n / 2);
+    assert!((frac - 0.5).abs() < 0.02, "i32 range is biased: {frac}");
+
+    let n = u64::MAX / 5 * 2;
+    let frac = fraction_below_half(|| random_from_u64range(0, n).unwrap() < n / 2);
+    assert!((frac - 0.5).abs() < 0.02, "prelude u64 range is biased: {frac}");
+}
+
+#[test]
+fn test_range_uniformity() {
+    // Critical values of the chi-square distribution for p = 1e-6
+    let mut counts = [0usize; 6];
+    for _ in 0..600000 {
+        counts[random_index(6).unwrap()] += 1;
+    }
+    assert!(chi_square(&counts) < 35.89, "random_index: {counts:?}");
+
+    let mut counts = [0usize; 101];
+    for _ in 0..1010000 {
+        counts[random_from_u64range(0, 100).unwrap() as usize] += 1;
+    }
+    assert!(chi_square(&counts) < 182.13, "random_from_u64range: {counts:?}");
+
+    let mut counts = [0usize; 8];
+    for _ in 0..800000 {
+        counts[(random_from_i32range(-3, 4).unwrap() + 3) as usize] += 1;
+    }
+    assert!(chi_square(&counts) < 40.52, "random_from_i32range: {counts:?}");
+}
+
+#[test]
+fn test_unit_floats() {
+    let mut tyche = Tyche::new().unwrap();
+    let mut counts = [0usize; 10];
+    let mut sum = 0.0;
+    for _ in 0..1000000 {
+        let x = tyche.random_unit_f64().unwrap();
+        assert!((0.0..1.0).contains(&x));
+        counts[(x * 10.0) as usize] += 1;
+        sum += x;
+    }
+    // 9 degrees of freedom, p = 1e-6
+    assert!(chi_square(&counts) < 46.0, "random_unit_f64: {counts:?}");
+    assert!((sum / 1000000.0 - 0.5).abs() < 0.002);
+
+    let mut counts = [0usize; 10];
+    for _ in 0..1000000 {
+        let x = tyche.random_unit_f32().unwrap();
+        assert!((0.0..1.0).contains(&x));
+        counts[(x * 10.0) as usize] += 1;
+    }
+    assert!(chi_square(&counts) < 46.0, "random_unit_f32: {counts:?}");
+
+    for _ in 0..100000 {
+        let x = tyche.random_unit_f64_open_closed().unwrap();
+        assert!(x > 0.0 && x <= 1.0);
+        let x = tyche.random_unit_f32_open_closed().unwrap();
+        assert!(x > 0.0 && x <= 1.0);
+        assert!((0.0..=1.0).contains(&tyche.random_unit_f64_closed().unwrap()));
+        assert!((0.0..=1.0).contains(&tyche.random_unit_f32_closed().unwrap()));
+    }
+}
+
+#[test]
+fn test_float_range_uniformity() {
+    let mut tyche = Tyche::new().unwrap();
+    let mut counts = [0usize; 10];
+    for _ in 0..1000000 {
+        let x = tyche.random_from_f64_range(-5.0, 5.0).unwrap();
+        assert!((-5.0..5.0).contains(&x));
+        counts[(x + 5.0) as usize] += 1;
+    }
+    assert!(chi_square(&counts) < 46.0, "random_from_f64_range: {counts:?}");
+
+    let mut counts = [0usize; 10];
+    for _ in 0..1000000 {
+        let x = tyche.random_from_f32_range(100.0, 110.0).unwrap();
+        assert!((100.0..110.0).contains(&x));
+        counts[(x - 100.0) as usize] += 1;
+    }
+    assert!(chi_square(&counts) < 46.0, "random_from_f32_range: {counts:?}");
+}
+
+#[test]
+fn test_float_range_extremes() {
+    let mut tyche = Tyche::new().unwrap();
+    let mut positive = 0;
+    for _ in 0..100000 {
+        let x = tyche.random_from_f64_range(f64::MIN, f64::MAX).unwrap();
+        assert!(x.is_finite());
+        let y = tyche.random_from_f32_range(f32::MIN, f32::MAX).unwrap();
+        assert!(y.is_finite());
+        if x > 0.0 {
+            positive += 1;
+        }
+    }
+    assert!((positive as f64 / 100000.0 - 0.5).abs() < 0.02);
+    assert!(tyche.random_from_f64_range(0.0, f64::INFINITY).is_err());
+    assert!(tyche.random_from_f32_range(f32::NEG_INFINITY, 0.0).is_err());
+    assert!(tyche.random_from_f64_range(1.0, 0.0).is_err());
+}
+
+#[test]
+fn test_signed_range_extremes() {
+    let mut tyche = Tyche::new().unwrap();
+    let (mut neg, mut pos) = (0, 0);
+    for _ in 0..100000 {
+        let a = tyche.random_from_i32_range(i32::MIN, i32::MAX).unwrap();
+        assert_ne!(a, i32::MAX);
+        let b = tyche.random_from_i64_range(i64::MIN, i64::MAX).unwrap();
+        assert_ne!(b, i64::MAX);
+        let c = tyche.random_from_i_range(isize::MIN, isize::MAX).unwrap();
+        assert_ne!(c, isize::MAX);
+        tyche.random_from_i32_range_inclusive(i32::MIN, i32::MAX).unwrap();
+        tyche.random_from_i64_range_inclusive(i64::MIN, i64::MAX).unwrap();
+        tyche.random_from_i_range_inclusive(isize::MIN, isize::MAX).unwrap();
+        random_from_i32range(i32::MIN, i32::MAX).unwrap();
+        if b < 0 {
+            neg += 1;
+        } else {
+            pos += 1;
+        }
+    }
+    // Both halves of the full domain are reached evenly
+    assert!((neg as f64 / (neg + pos) as f64 - 0.5).abs() < 0.02);
+
+    // Ranges touching the bounds
+    for _ in 0..1000 {
+        assert_eq!(tyche.random_from_i32_range(i32::MAX - 1, i32::MAX).unwrap(), i32::MAX - 1);
+        assert_eq!(tyche.random_from_i64_range(i64::MIN, i64::MIN + 1).unwrap(), i64::MIN);
+        assert_eq!(tyche.random_from_i_range(isize::MIN, isize::MIN + 1).unwrap(), isize::MIN);
+        assert_eq!(tyche.random_from_i32_range_inclusive(i32::MAX, i32::MAX).unwrap(), i32::MAX);
+        assert_eq!(tyche.random_from_i64_range_inclusive(i64::MIN, i64::MIN).unwrap(), i64::MIN);
+        assert_eq!(tyche.random_from_i_range_inclusive(isize::MAX, isize::MAX).unwrap(), isize::MAX);
+        assert_eq!(random_from_i32range(i32::MIN, i32::MIN).unwrap(), i32::MIN);
+        let d = tyche.random_from_i64_range_inclusive(i64::MAX - 1, i64::MAX).unwrap();
+        assert!(d >= i64::MAX - 1);
+        let e = tyche.random_from_i32_range_inclusive(i32::MIN, i32::MIN + 1).unwrap();
+        assert!(e <= i32::MIN + 1);
+        let f = random_from_i32range(i32::MAX - 1, i32::MAX).unwrap();
+        assert!(f >= i32::MAX - 1);
+    }
+
+    // Both ends of inclusive ranges are reachable
+    let (mut min_found, mut max_found) = (false, false);
+    for _ in 0..1000 {
+        match tyche.random_from_i64_range_inclusive(i64::MAX - 2, i64::MAX).unwrap() {
+            i64::MAX => max_found = true,
+            x if x == i64::MAX - 2 => min_found = true,
+            _ => {}
+        }
+    }
+    assert!(min_found && max_found);
+
+    assert!(tyche.random_from_i64_range_inclusive(1, 0).is_err());
+    assert!(tyche.random_from_i32_range(i32::MAX, i32::MIN).is_err());
+}
+
+#[test]
+fn test_random_128() {
+    // Bytes are composed little-endian, like every narrower integer
+    let mut tyche = Tyche::with_source(Counter(0));
+    let expected = u128::from_le_bytes(std::array::from_fn(|i| i as u8));
+    assert_eq!(tyche.random_u128().unwrap(), expected);
+    let expected = i128::from_le_bytes(std::array::from_fn(|i| i as u8 + 16));
+    assert_eq!(tyche.random_i128().unwrap(), expected);
+
+    for _ in 0..1500 {
+        assert!(random_u128().is_ok());
+        assert!(random_i128().is_ok());
+    }
+
+    let mut tyche = Tyche::new().unwrap();
+    let n = u128::MAX / 5 * 2;
+    let frac = fraction_below_half(|| tyche.random_from_u128_range(0, n).unwrap() < n / 2);
+    assert!((frac - 0.5).abs() < 0.02, "u128 range is biased: {frac}");
+    let frac = fraction_below_half(|| tyche.random_u128_with_ceiling(n).unwrap() < n / 2);
+    assert!((frac - 0.5).abs() < 0.02, "u128 ceiling is biased: {frac}");
+
+    let mut counts = [0usize; 6];
+    for _ in 0..600000 {
+        counts[random_from_u128range(10, 15).unwrap() as usize - 10] += 1;
+    }
+    assert!(chi_square(&counts) < 35.89, "random_from_u128range: {counts:?}");
+
+    let (mut neg, mut pos) = (0, 0);
+    for _ in 0..100000 {
+        let x = tyche.random_from_i128_range(i128::MIN, i128::MAX).unwrap();
+        assert_ne!(x, i128::MAX);
+        if x < 0 {
+            neg += 1;
+        } else {
+            pos += 1;
+        }
+        tyche.random_from_i128_range_inclusive(i128::MIN, i128::MAX).unwrap();
+        tyche.random_from_u128_range_inclusive(0, u128::MAX).unwrap();
+        assert!(tyche.random_u128_with_ceiling(5).unwrap() <= 5);
+    }
+    assert!((neg as f64 / (neg + pos) as f64 - 0.5).abs() < 0.02);
+    for _ in 0..1000 {
+        let x = tyche.random_from_i128_range_inclusive(i128::MAX - 1, i128::MAX).unwrap();
+        assert!(x >= i128::MAX - 1);
+        assert_eq!(tyche.random_from_i128_range(i128::MIN, i128::MIN + 1).unwrap(), i128::MIN);
+        assert_eq!(tyche.random_from_u128_range(u128::MAX - 1, u128::MAX).unwrap(), u128::MAX - 1);
+        assert_eq!(tyche.random_u128_with_ceiling(0).unwrap(), 0);
+    }
+    assert!(tyche.random_from_u128_range(1, 0).is_err());
+    assert!(tyche.random_from_i128_range_inclusive(0, -1).is_err());
+    assert!(random_from_u128range(1, 0).is_err());
+}
+
+#[test]
+fn test_gen_range_integers() {
+    let mut tyche = Tyche::new().unwrap();
+    let mut counts = [0usize; 256];
+    for _ in 0..256000 {
+        counts[tyche.gen_range(0u8..=255).unwrap() as usize] += 1;
+    }
+    // 255 degrees of freedom, p = 1e-6
+    assert!(chi_square(&counts) < 377.08, "gen_range u8: {counts:?}");
+
+    let mut counts = [0usize; 6];
+    for _ in 0..600000 {
+        counts[(tyche.gen_range(-3i16..3).unwrap() + 3) as usize] += 1;
+    }
+    assert!(chi_square(&counts) < 35.89, "gen_range i16: {counts:?}");
+
+    for _ in 0..10000 {
+        assert!((1..=6).contains(&tyche.gen_range(1u32..=6).unwrap()));
+        assert!((10..20).contains(&tyche.gen_range(10usize..20).unwrap()));
+        assert!(tyche.gen_range(..-5i8).unwrap() < -5);
+        assert!(tyche.gen_range(..=i64::MIN + 1).unwrap() <= i64::MIN + 1);
+        assert!(tyche.gen_range(u64::MAX - 1..).unwrap() >= u64::MAX - 1);
+        assert!(tyche.gen_range(i128::MAX - 1..).unwrap() >= i128::MAX - 1);
+        assert_ne!(tyche.gen_range(isize::MIN..isize::MAX).unwrap(), isize::MAX);
+        let _: u128 = tyche.gen_range(..).unwrap();
+        tyche.gen_range(i32::MIN..=i32::MAX).unwrap();
+        assert_eq!(tyche.gen_range(7u16..=7).unwrap(), 7);
+        assert_eq!(tyche.gen_range(-1i32..0).unwrap(), -1);
+        assert_eq!(gen_range(u8::MAX..).unwrap(), u8::MAX);
+    }
+
+    // Both ends of the range are reachable
+    let (mut low, mut high) = (false, false);
+    for _ in 0..1000 {
+        match tyche.gen_range(i8::MIN..=i8::MIN + 1).unwrap() {
+            i8::MIN => low = true,
+            _ => high = true,
+        }
+    }
+    assert!(low && high);
+
+    assert!(tyche.gen_range(5..5).is_err());
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed = tyche.gen_range(5u32..=4);
+    assert!(reversed.is_err());
+    assert!(tyche.gen_range(..i32::MIN).is_err());
+    assert!(gen_range(0usize..0).is_err());
+}
+
+#[test]
+fn test_gen_range_floats() {
+    let mut tyche = Tyche::new().unwrap();
+    let mut counts = [0usize; 10];
+    for _ in 0..1000000 {
+        let x = tyche.gen_range(0.0..10.0).unwrap();
+        assert!((0.0..10.0).contains(&x));
+        counts[x as usize] += 1;
+    }
+    assert!(chi_square(&counts) < 46.0, "gen_range f64: {counts:?}");
+
+    for _ in 0..100000 {
+        let x: f32 = tyche.gen_range(-1.0..=1.0).unwrap();
+        assert!((-1.0..=1.0).contains(&x));
+        assert!(tyche.gen_range(..0.0f64).unwrap() < 0.0);
+        assert!(tyche.gen_range(..=0.0f32).unwrap() <= 0.0);
+        let y: f64 = tyche.gen_range(1e300..).unwrap();
+        assert!(y >= 1e300 && y.is_finite());
+        assert!(tyche.gen_range(f64::MIN..=f64::MAX).unwrap().is_finite());
+        assert!(gen_range(0.5f32..0.75).unwrap() < 0.75);
+    }
+    assert_eq!(tyche.gen_range(2.5..=2.5).unwrap(), 2.5);
+    assert!(tyche.gen_range(2.5..2.5).is_err());
+    assert!(tyche.gen_range(0.0..f64::INFINITY).is_err());
+    assert!(tyche.gen_range(f32::NAN..1.0).is_err());
+    assert!(tyche.gen_range(0.0..=f64::NAN).is_err());
+    assert!(tyche.gen_range(f64::MAX..).is_ok());
+}
+
+/// Count how often each distinct arrangement produced by `draw` occurs in `rounds` draws.
+fn count_arrangements<F: FnMut() -> Vec<u8>>(rounds: usize, mut draw: F) -> Vec<usize> {
+    let mut counts = std::collections::HashMap::new();
+    for _ in 0..rounds {
+        *counts.entry(draw()).or_insert(0usize) += 1;
+    }
+    counts.into_values().collect()
+}
+
+#[test]
+fn test_shuffle() {
+    let mut tyche = Tyche::new().unwrap();
+
+    let counts = count_arrangements(600000, || {
+        let mut v = vec![0, 1, 2];
+        tyche.shuffle(&mut v).unwrap();
+        v
+    });
+    assert_eq!(counts.len(), 6);
+    assert!(chi_square(&counts) < 35.89, "shuffle of 3: {counts:?}");
+
+    let counts = count_arrangements(960000, || {
+        let mut v = vec![0, 1, 2, 3];
+        shuffle(&mut v).unwrap();
+        v
+    });
+    // 23 degrees of freedom, p = 1e-6
+    assert_eq!(counts.len(), 24);
+    assert!(chi_square(&counts) < 70.55, "shuffle of 4: {counts:?}");
+
+    let mut empty: [u8; 0] = [];
+    tyche.shuffle(&mut empty).unwrap();
+    let mut one = [1];
+    tyche.shuffle(&mut one).unwrap();
+    assert_eq!(one, [1]);
+    let mut many = (0..1000).collect::<Vec<u32>>();
+    tyche.shuffle(&mut many).unwrap();
+    many.sort_unstable();
+    assert_eq!(many, (0..1000).collect::<Vec<u32>>());
+}
+
+#[test]
+fn test_partial_shuffle() {
+    let mut tyche = Tyche::new().unwrap();
+
+    // Every ordered pair out of 4 elements is equally likely
+    let counts = count_arrangements(600000, || {
+        let mut v = vec![0, 1, 2, 3];
+        let (chosen, rest) = tyche.partial_shuffle(&mut v, 2).unwrap();
+        assert_eq!(rest.len(), 2);
+        chosen.to_vec()
+    });
+    assert_eq!(counts.len(), 12);
+    assert!(chi_square(&counts) < 48.87, "partial shuffle: {counts:?}");
+
+    let mut v = (0..10).collect::<Vec<u8>>();
+    let (chosen, rest) = partial_shuffle(&mut v, 100).unwrap();
+    assert_eq!((chosen.len(), rest.len()), (10, 0));
+    let (chosen, rest) = tyche.partial_shuffle(&mut v, 0).unwrap();
+    assert_eq!((chosen.len(), rest.len()), (0, 10));
+    v.sort_unstable();
+    assert_eq!(v, (0..10).collect::<Vec<u8>>());
+}
+
+#[test]
+fn test_choose() {
+    let mut tyche = Tyche::new().unwrap();
+    let items = ['a', 'b', 'c', 'd', 'e', 'f'];
+    let mut counts = [0usize; 6];
+    for _ in 0..600000 {
+        let c = tyche.choose(&items).unwrap();
+        counts[(*c as u8 - b'a') as usize] += 1;
+    }
+    assert!(chi_square(&counts) < 35.89, "choose: {counts:?}");
+
+    let mut items = [0u32; 4];
+    for _ in 0..1000 {
+        *tyche.choose_mut(&mut items).unwrap() += 1;
+    }
+    assert_eq!(items.iter().sum::<u32>(), 1000);
+    assert!(items.iter().all(|&n| n > 0));
+
+    let empty: [u8; 0] = [];
+    assert!(tyche.choose(&empty).is_err());
+    let empty_mut: &mut [u8] = &mut [];
+    assert!(tyche.choose_mut(empty_mut).is_err());
+    assert!(choose(&empty).is_err());
+    assert_eq!(*choose(&[42]).unwrap(), 42);
+}
+
+#[test]
+fn test_choose_multiple() {
+    let mut tyche = Tyche::new().unwrap();
+    let items = [0u8, 1, 2, 3];
+    let counts = count_arrangements(600000, || {
+        let chosen = tyche.choose_multiple(&items, 2).unwrap();
+        assert_ne!(chosen[0], chosen[1]);
+        chosen.into_iter().copied().collect()
+    });
+    assert_eq!(counts.len(), 12);
+    assert!(chi_square(&counts) < 48.87, "choose_multiple: {counts:?}");
+
+    let mut all = choose_multiple(&items, 4).unwrap();
+    all.sort_unstable();
+    assert_eq!(all, vec![&0, &1, &2, &3]);
+    assert!(tyche.choose_multiple(&items, 0).unwrap().is_empty());
+    assert!(tyche.choose_multiple(&items, 5).is_err());
+    assert!(tyche.choose_multiple(&[] as &[u8], 0).unwrap().is_empty());
+}
+
+#[test]
+fn test_choose_weighted() {
+    let mut tyche = Tyche::new().unwrap();
+    let items = [(0usize, 1.0), (1, 0.0), (2, 2.0), (3, 3.0), (4, 0.0)];
+    let mut counts = [0usize; 5];
+    for _ in 0..600000 {
+        counts[tyche.choose_weighted(&items, |item| item.1).unwrap().0] += 1;
+    }
+    assert_eq!(counts[1] + counts[4], 0);
+    let expected = [100000.0, 200000.0, 300000.0];
+    let stat: f64 = [counts[0], counts[2], counts[3]]
+        .iter()
+        .zip(expected)
+        .map(|(&c, e)| (c as f64 - e).powi(2) / e)
+        .sum();
+    // 2 degrees of freedom, p = 1e-6
+    assert!(stat < 27.63, "choose_weighted: {counts:?}");
+
+    assert_eq!(*choose_weighted(&[7, 8], |&x| if x == 8 { 1.0 } else { 0.0 }).unwrap(), 8);
+    assert!(tyche.choose_weighted(&[] as &[u8], |_| 1.0).is_err());
+    assert!(tyche.choose_weighted(&[1, 2], |_| 0.0).is_err());
+    assert!(tyche.choose_weighted(&[1, 2], |&x| if x == 1 { -1.0 } else { 2.0 }).is_err());
+    assert!(tyche.choose_weighted(&[1, 2], |_| f64::NAN).is_err());
+    assert!(tyche.choose_weighted(&[1, 2], |_| f64::INFINITY).is_err());
+    assert!(tyche.choose_weighted(&[1, 2], |_| f64::MAX).is_err());
+}
+
+#[test]
+fn test_choose_from_iter() {
+    let mut tyche = Tyche::new().unwrap();
+    // Filtering hides the length, forcing the single pass reservoir
+    let mut counts = [0usize; 6];
+    for _ in 0..300000 {
+        counts[tyche.choose_from_iter((0..6).filter(|_| true)).unwrap()] += 1;
+    }
+    assert!(chi_square(&counts) < 35.89, "choose_from_iter: {counts:?}");
+
+    // Exact size iterators jump to the chosen item
+    let mut counts = [0usize; 6];
+    for _ in 0..600000 {
+        counts[tyche.choose_from_iter(0..6).unwrap()] += 1;
+    }
+    assert!(chi_square(&counts) < 35.89, "choose_from_iter exact: {counts:?}");
+
+    assert_eq!(choose_from_iter(std::iter::once(3)).unwrap(), 3);
+    assert!(tyche.choose_from_iter(std::iter::empty::<u8>()).is_err());
+    assert!(tyche.choose_from_iter((0..0).filter(|_| true)).is_err());
+}
+
+#[test]
+fn test_sample_from_iter() {
+    let mut tyche = Tyche::new().unwrap();
+    // Every item ends up in the sample with probability k / n
+    let mut counts = [0usize; 10];
+    for _ in 0..100000 {
+        let sample = tyche.sample_from_iter((0..10).filter(|_| true), 3).unwrap();
+        assert_eq!(sample.len(), 3);
+        for item in sample {
+            counts[item] += 1;
+        }
+    }
+    assert!(chi_square(&counts) < 44.81, "sample_from_iter: {counts:?}");
+
+    // So does every subset, and every order
+    let counts = count_arrangements(240000, || {
+        tyche.sample_from_iter(0..4u8, 2).unwrap()
+    });
+    assert_eq!(counts.len(), 12);
+    assert!(chi_square(&counts) < 48.87, "sample_from_iter orders: {counts:?}");
+
+    // Long streams are mostly skipped over, which must not favour any part of the stream
+    let mut counts = [0usize; 10];
+    for _ in 0..20000 {
+        for item in tyche.sample_from_iter((0..10000).filter(|_| true), 5).unwrap() {
+            counts[item / 1000] += 1;
+        }
+    }
+    assert!(chi_square(&counts) < 44.81, "sample_from_iter long stream: {counts:?}");
+
+    let mut sample = sample_from_iter(0..1000000u32, 100).unwrap();
+    sample.sort_unstable();
+    sample.dedup();
+    assert_eq!(sample.len(), 100);
+    assert!(sample.iter().all(|&n| n < 1000000));
+
+    let mut short = tyche.sample_from_iter(0..3, 5).unwrap();
+    short.sort_unstable();
+    assert_eq!(short, vec![0, 1, 2]);
+    assert!(tyche.sample_from_iter(0..3, 0).unwrap().is_empty());
+    assert!(tyche.sample_from_iter(std::iter::empty::<u8>(), 2).unwrap().is_empty());
+}
+
+/// Check that `sample_indices(length, amount)` returns distinct indices, each included equally often.
+fn check_sample_indices(length: usize, amount: usize, rounds: usize, critical: f64) {
+    let mut tyche = Tyche::new().unwrap();
+    let buckets = length.min(10);
+    let mut counts = vec![0usize; buckets];
+    for _ in 0..rounds {
+        let indices = tyche.sample_indices(length, amount).unwrap();
+        assert_eq!(indices.len(), amount);
+        let mut sorted = indices.clone().into_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), amount);
+        for index in &indices {
+            assert!(index < length);
+            counts[index * buckets / length] += 1;
+        }
+    }
+    assert!(chi_square(&counts) < critical, "sample_indices({length}, {amount}): {counts:?}");
+}
+
+#[test]
+fn test_sample_indices() {
+    // Floyd's algorithm
+    check_sample_indices(10, 3, 100000, 44.81);
+    check_sample_indices(1000, 32, 10000, 44.81);
+    // Partial shuffle
+    check_sample_indices(100, 60, 10000, 44.81);
+    check_sample_indices(200, 200, 100, 44.81);
+    // Rejection sampling
+    check_sample_indices(1000000, 1000, 100, 44.81);
+
+    // Floyd's algorithm keeps the order random as well
+    let mut tyche = Tyche::new().unwrap();
+    let counts = count_arrangements(240000, || {
+        let indices = tyche.sample_indices(4, 2).unwrap();
+        indices.iter().map(|i| i as u8).collect()
+    });
+    assert_eq!(counts.len(), 12);
+    assert!(chi_square(&counts) < 48.87, "sample_indices orders: {counts:?}");
+
+    assert!(matches!(tyche.sample_indices(10, 5).unwrap(), IndexVec::U32(_)));
+    #[cfg(target_pointer_width = "64")]
+    {
+        let indices = tyche.sample_indices(u32::MAX as usize + 10, 5).unwrap();
+        assert!(matches!(indices, IndexVec::USize(_)));
+        assert_eq!(indices.len(), 5);
+    }
+    assert!(tyche.sample_indices(0, 0).unwrap().is_empty());
+    assert!(tyche.sample_indices(3, 4).is_err());
+    assert_eq!(sample_indices(5, 5).unwrap().len(), 5);
+}
+
+/// Pearson's chi-square statistic of `counts` against `weights`, scaled to the number of draws.
+fn chi_square_weighted(counts: &[usize], weights: &[f64]) -> f64 {
+    let total: usize = counts.iter().sum();
+    let weight_total: f64 = weights.iter().sum();
+    counts
+        .iter()
+        .zip(weights)
+        .filter(|(_, w)| **w > 0.0)
+        .map(|(&c, &w)| {
+            let expected = total as f64 * w / weight_total;
+            (c as f64 - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+#[test]
+fn test_weighted_index() {
+    let mut tyche = Tyche::new().unwrap();
+    let weights = [1.0, 0.0, 2.0, 3.0, 4.0, 0.5];
+    let table = WeightedIndex::new(weights).unwrap();
+    assert_eq!(table.len(), 6);
+    assert_eq!(table.total_weight(), 10.5);
+    let mut counts = [0usize; 6];
+    for _ in 0..1000000 {
+        counts[table.sample(&mut tyche).unwrap()] += 1;
+    }
+    assert_eq!(counts[1], 0);
+    // 4 degrees of freedom, p = 1e-6
+    assert!(chi_square_weighted(&counts, &weights) < 33.38, "alias table: {counts:?}");
+
+    // Integer weights of any width
+    let table = WeightedIndex::new([1u8, 1, 2]).unwrap();
+    let mut counts = [0usize; 3];
+    for _ in 0..400000 {
+        counts[table.sample(&mut tyche).unwrap()] += 1;
+    }
+    assert!(chi_square_weighted(&counts, &[1.0, 1.0, 2.0]) < 27.63, "u8 weights: {counts:?}");
+    assert!(WeightedIndex::new([u64::MAX, 1]).is_ok());
+    assert!(WeightedIndex::new(vec![-1i32, 5]).is_err());
+    assert_eq!(WeightedIndex::new([7usize]).unwrap().sample(&mut tyche).unwrap(), 0);
+}
+
+#[test]
+fn test_weighted_index_errors() {
+    assert_eq!(WeightedIndex::new(Vec::<f64>::new()), Err(WeightError::NoItem));
+    assert_eq!(WeightedIndex::new([0u32, 0, 0]), Err(WeightError::AllWeightsZero));
+    assert_eq!(
+        WeightedIndex::new([1.0, -0.5]),
+        Err(WeightError::InvalidWeight { index: 1 })
+    );
+    assert_eq!(
+        WeightedIndex::new([f64::NAN, 1.0]),
+        Err(WeightError::InvalidWeight { index: 0 })
+    );
+    assert_eq!(
+        WeightedIndex::new([1.0, f32::INFINITY as f64]),
+        Err(WeightError::InvalidWeight { index: 1 })
+    );
+    assert_eq!(WeightedIndex::new([f64::MAX, f64::MAX]), Err(WeightError::TotalOverflow));
+}
+
+#[test]
+fn test_weighted_index_update() {
+    let mut tyche = Tyche::new().unwrap();
+    let mut table = WeightedIndex::new([1u32, 1, 1, 1]).unwrap();
+    table.update_weight(0, 0u32).unwrap();
+    table.update_weights(&[(3, 5.0), (2, 0.0)]).unwrap();
+    assert_eq!(table.weight(3), Some(5.0));
+    assert_eq!(table.total_weight(), 6.0);
+    let mut counts = [0usize; 4];
+    for _ in 0..600000 {
+        counts[table.sample(&mut tyche).unwrap()] += 1;
+    }
+    assert_eq!(counts[0] + counts[2], 0);
+    assert!(chi_square_weighted(&counts, &[0.0, 1.0, 0.0, 5.0]) < 23.93, "updated: {counts:?}");
+
+    // Failed updates leave the table untouched
+    let before = table.clone();
+    assert_eq!(
+        table.update_weights(&[(0, 2.0), (4, 1.0)]),
+        Err(WeightError::IndexOutOfBounds { index: 4 })
+    );
+    assert_eq!(table.update_weights(&[(1, 0), (3, 0)]), Err(WeightError::AllWeightsZero));
+    assert_eq!(
+        table.update_weight(1, f64::NAN),
+        Err(WeightError::InvalidWeight { index: 1 })
+    );
+    assert_eq!(table, before);
+}
@@ -0,0 +1,57 @@
+//! A lazily initialised, per-thread `Tyche` instance.
+
+use crate::Tyche;
+use crate::macros::rng_api_methods;
+use athena::rng_api::RngApi;
+use std::cell::RefCell;
+use std::io::Error as IoError;
+use std::marker::PhantomData;
+
+thread_local! {
+    static THREAD_TYCHE: RefCell<Option<Tyche>> = const { RefCell::new(None) };
+}
+
+/// A handle to the calling thread's shared `Tyche` instance.
+///
+/// The instance is created on first use and reused by every handle and every `prelude`
+/// function on the same thread, so they share one entropy buffer instead of opening a new
+/// source per call. Handles are cheap to create and cannot be sent to other threads.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ThreadRng {
+    _not_send: PhantomData<*const ()>,
+}
+
+/// Return a handle to the calling thread's shared `Tyche` instance.
+///
+/// ## Example
+/// ```
+/// use athena::rng_api::RngApi;
+///
+/// let mut rng = tyche::thread_rng();
+/// let roll = rng.random_from_range_inclusive(1, 6).unwrap();
+/// assert!((1..=6).contains(&roll));
+/// ```
+pub fn thread_rng() -> ThreadRng {
+    ThreadRng {
+        _not_send: PhantomData,
+    }
+}
+
+impl ThreadRng {
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), IoError> {
+        THREAD_TYCHE
+            .try_with(|cell| {
+                let mut slot = cell.borrow_mut();
+                let tyche = match &mut *slot {
+                    Some(tyche) => tyche,
+                    empty => empty.insert(Tyche::new()?),
+                };
+                tyche.fill(buffer)
+            })
+            .map_err(|_| IoError::other("The thread local Tyche instance was already destroyed!"))?
+    }
+}
+
+impl RngApi for ThreadRng {
+    rng_api_methods!();
+}
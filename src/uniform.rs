@@ -0,0 +1,307 @@
+//! Unbiased sampling of integers below a bound.
+//!
+//! Reducing a random word with `% n` favours small results whenever `n` is not a power of two.
+//! These helpers use Lemire's multiply-and-reject method instead: the random word is multiplied
+//! by `n` and the high half is the result, rejecting the few words that would make some results
+//! more likely than others. The rejection test needs a division only in the rare case it could fail.
+//!
+//! See Daniel Lemire, "Fast Random Integer Generation in an Interval", ACM TOMACS 2019.
+//!
+//! Floats are sampled by scaling a uniform float from `[0, 1)` onto the range.
+//!
+//! [`SampleUniform`] ties these together for `gen_range`, for every primitive integer and float.
+
+use crate::RngApiExt;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::fmt::Debug;
+use std::ops::Bound;
+
+/// Uniform `u32` in `[0, n)`. `n` must not be 0.
+pub(crate) fn u32_below<R: RngApi + ?Sized>(rng: &mut R, n: u32) -> RngResult<u32> {
+    debug_assert!(n > 0);
+    let mut m = u64::from(rng.random_u32()?) * u64::from(n);
+    if (m as u32) < n {
+        let threshold = n.wrapping_neg() % n;
+        while (m as u32) < threshold {
+            m = u64::from(rng.random_u32()?) * u64::from(n);
+        }
+    }
+    Ok((m >> 32) as u32)
+}
+
+/// Uniform `u64` in `[0, n)`. `n` must not be 0.
+pub(crate) fn u64_below<R: RngApi + ?Sized>(rng: &mut R, n: u64) -> RngResult<u64> {
+    debug_assert!(n > 0);
+    let mut m = u128::from(rng.random_u64()?) * u128::from(n);
+    if (m as u64) < n {
+        let threshold = n.wrapping_neg() % n;
+        while (m as u64) < threshold {
+            m = u128::from(rng.random_u64()?) * u128::from(n);
+        }
+    }
+    Ok((m >> 64) as u64)
+}
+
+/// Uniform `usize` in `[0, n)`. `n` must not be 0.
+pub(crate) fn usize_below<R: RngApi + ?Sized>(rng: &mut R, n: usize) -> RngResult<usize> {
+    #[cfg(target_pointer_width = "64")]
+    {
+        Ok(u64_below(rng, n as u64)? as usize)
+    }
+    #[cfg(target_pointer_width = "32")]
+    {
+        Ok(u32_below(rng, n as u32)? as usize)
+    }
+}
+
+/// Uniform `u128` in `[0, n)`. `n` must not be 0.
+///
+/// Lemire's method would need a 256-bit product here, so this rejects the `2^128 mod n` lowest
+/// words instead, after which `% n` is exact.
+pub(crate) fn u128_below<R: RngApi + ?Sized>(rng: &mut R, n: u128) -> RngResult<u128> {
+    debug_assert!(n > 0);
+    let threshold = n.wrapping_neg() % n;
+    loop {
+        let x = rng.random_u128()?;
+        if x >= threshold {
+            return Ok(x % n);
+        }
+    }
+}
+
+/// Uniform `u32` in `[0, max]`.
+pub(crate) fn u32_up_to<R: RngApi + ?Sized>(rng: &mut R, max: u32) -> RngResult<u32> {
+    match max.checked_add(1) {
+        Some(n) => u32_below(rng, n),
+        None => rng.random_u32(),
+    }
+}
+
+/// Uniform `u64` in `[0, max]`.
+pub(crate) fn u64_up_to<R: RngApi + ?Sized>(rng: &mut R, max: u64) -> RngResult<u64> {
+    match max.checked_add(1) {
+        Some(n) => u64_below(rng, n),
+        None => rng.random_u64(),
+    }
+}
+
+/// Uniform `usize` in `[0, max]`.
+pub(crate) fn usize_up_to<R: RngApi + ?Sized>(rng: &mut R, max: usize) -> RngResult<usize> {
+    match max.checked_add(1) {
+        Some(n) => usize_below(rng, n),
+        None => rng.random_usize(),
+    }
+}
+
+/// Uniform `u128` in `[0, max]`.
+pub(crate) fn u128_up_to<R: RngApi + ?Sized>(rng: &mut R, max: u128) -> RngResult<u128> {
+    match max.checked_add(1) {
+        Some(n) => u128_below(rng, n),
+        None => rng.random_u128(),
+    }
+}
+
+fn check_finite<F: std::fmt::Display>(low: F, high: F, finite: bool) -> RngResult<()> {
+    if finite {
+        Ok(())
+    } else {
+        Err(RngError::Generic(format!(
+            "Range bounds '{low}' and '{high}' must be finite!"
+        )))
+    }
+}
+
+/// Uniform `f32` in `[low, high)`. Requires `low < high`.
+///
+/// ## Errors
+/// Returns `RngError` if either bound is not finite.
+pub(crate) fn f32_in<R: RngApi + ?Sized>(rng: &mut R, low: f32, high: f32) -> RngResult<f32> {
+    check_finite(low, high, low.is_finite() && high.is_finite())?;
+    let scale = high - low;
+    loop {
+        let u = rng.random_unit_f32()?;
+        let x = if scale.is_finite() {
+            low + scale * u
+        } else {
+            // `high - low` overflows for ranges wider than `f32::MAX`, halve it instead
+            let half = (high * 0.5 - low * 0.5) * u;
+            low + half + half
+        };
+        // Rounding can land exactly on `high`, which the range excludes
+        if x < high {
+            return Ok(x);
+        }
+    }
+}
+
+/// Uniform `f64` in `[low, high)`. Requires `low < high`.
+///
+/// ## Errors
+/// Returns `RngError` if either bound is not finite.
+pub(crate) fn f64_in<R: RngApi + ?Sized>(rng: &mut R, low: f64, high: f64) -> RngResult<f64> {
+    check_finite(low, high, low.is_finite() && high.is_finite())?;
+    let scale = high - low;
+    loop {
+        let u = rng.random_unit_f64()?;
+        let x = if scale.is_finite() {
+            low + scale * u
+        } else {
+            // `high - low` overflows for ranges wider than `f64::MAX`, halve it instead
+            let half = (high * 0.5 - low * 0.5) * u;
+            low + half + half
+        };
+        // Rounding can land exactly on `high`, which the range excludes
+        if x < high {
+            return Ok(x);
+        }
+    }
+}
+
+/// Uniform `f32` in `[low, high]`. Requires `low <= high`.
+///
+/// ## Errors
+/// Returns `RngError` if either bound is not finite.
+pub(crate) fn f32_in_inclusive<R: RngApi + ?Sized>(
+    rng: &mut R,
+    low: f32,
+    high: f32,
+) -> RngResult<f32> {
+    check_finite(low, high, low.is_finite() && high.is_finite())?;
+    let u = rng.random_unit_f32_closed()?;
+    let scale = high - low;
+    let x = if scale.is_finite() {
+        low + scale * u
+    } else {
+        let half = (high * 0.5 - low * 0.5) * u;
+        low + half + half
+    };
+    Ok(x.min(high))
+}
+
+/// Uniform `f64` in `[low, high]`. Requires `low <= high`.
+///
+/// ## Errors
+/// Returns `RngError` if either bound is not finite.
+pub(crate) fn f64_in_inclusive<R: RngApi + ?Sized>(
+    rng: &mut R,
+    low: f64,
+    high: f64,
+) -> RngResult<f64> {
+    check_finite(low, high, low.is_finite() && high.is_finite())?;
+    let u = rng.random_unit_f64_closed()?;
+    let scale = high - low;
+    let x = if scale.is_finite() {
+        low + scale * u
+    } else {
+        let half = (high * 0.5 - low * 0.5) * u;
+        low + half + half
+    };
+    Ok(x.min(high))
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A type [`gen_range`](crate::RngApiExt::gen_range) can sample uniformly from a range.
+///
+/// Implemented for every primitive integer and float type. Integer ranges are unbiased over
+/// the full domain of the type; float ranges scale a uniform float in `[0, 1)` or `[0, 1]`.
+pub trait SampleUniform: Sized + Copy + PartialOrd + Debug + private::Sealed {
+    /// Sample uniformly between `low` and `high`.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the range is empty, a float bound is not finite or the generator fails.
+    fn sample_bounds<R: RngApi + ?Sized>(
+        rng: &mut R,
+        low: Bound<&Self>,
+        high: Bound<&Self>,
+    ) -> RngResult<Self>;
+}
+
+fn empty_range<T: Debug>(low: Bound<&T>, high: Bound<&T>) -> RngError {
+    RngError::Generic(format!("Range from {low:?} to {high:?} is empty!"))
+}
+
+/// Implement `SampleUniform` for an integer type `$t`, whose unsigned counterpart is `$u`,
+/// sampling through the unsigned `$up_to` helper over `$w`.
+macro_rules! impl_sample_int {
+    ($($t:ty, $u:ty, $w:ty, $up_to:ident);* $(;)?) => {$(
+        impl private::Sealed for $t {}
+
+        impl SampleUniform for $t {
+            fn sample_bounds<R: RngApi + ?Sized>(
+                rng: &mut R,
+                low: Bound<&Self>,
+                high: Bound<&Self>,
+            ) -> RngResult<Self> {
+                let min = match low {
+                    Bound::Included(&min) => Some(min),
+                    Bound::Excluded(&min) => min.checked_add(1),
+                    Bound::Unbounded => Some(<$t>::MIN),
+                };
+                let max = match high {
+                    Bound::Included(&max) => Some(max),
+                    Bound::Excluded(&max) => max.checked_sub(1),
+                    Bound::Unbounded => Some(<$t>::MAX),
+                };
+                match (min, max) {
+                    (Some(min), Some(max)) if min <= max => {
+                        // Two's complement: the difference is exact when read as unsigned
+                        let span = max.wrapping_sub(min) as $u as $w;
+                        Ok(min.wrapping_add($up_to(rng, span)? as $u as $t))
+                    }
+                    _ => Err(empty_range(low, high)),
+                }
+            }
+        }
+    )*};
+}
+
+impl_sample_int!(
+    u8, u8, u32, u32_up_to;
+    u16, u16, u32, u32_up_to;
+    u32, u32, u32, u32_up_to;
+    u64, u64, u64, u64_up_to;
+    u128, u128, u128, u128_up_to;
+    usize, usize, usize, usize_up_to;
+    i8, u8, u32, u32_up_to;
+    i16, u16, u32, u32_up_to;
+    i32, u32, u32, u32_up_to;
+    i64, u64, u64, u64_up_to;
+    i128, u128, u128, u128_up_to;
+    isize, usize, usize, usize_up_to;
+);
+
+/// Implement `SampleUniform` for a float type `$t` using the `$half_open` and `$closed` helpers.
+macro_rules! impl_sample_float {
+    ($($t:ty, $half_open:ident, $closed:ident);* $(;)?) => {$(
+        impl private::Sealed for $t {}
+
+        impl SampleUniform for $t {
+            fn sample_bounds<R: RngApi + ?Sized>(
+                rng: &mut R,
+                low: Bound<&Self>,
+                high: Bound<&Self>,
+            ) -> RngResult<Self> {
+                let min = match low {
+                    Bound::Included(&min) => min,
+                    Bound::Excluded(&min) => min.next_up(),
+                    Bound::Unbounded => <$t>::MIN,
+                };
+                match high {
+                    Bound::Excluded(&max) if min < max => $half_open(rng, min, max),
+                    Bound::Included(&max) if min <= max => $closed(rng, min, max),
+                    Bound::Unbounded if min <= <$t>::MAX => $closed(rng, min, <$t>::MAX),
+                    // Also reached by NaN bounds, which compare false to everything
+                    _ => Err(empty_range(low, high)),
+                }
+            }
+        }
+    )*};
+}
+
+impl_sample_float!(
+    f32, f32_in, f32_in_inclusive;
+    f64, f64_in, f64_in_inclusive;
+);
@@ -0,0 +1,240 @@
+//! Weighted sampling of indices in constant time with Walker's alias method.
+
+use crate::RngApiExt;
+use crate::distributions::Distribution;
+use crate::uniform;
+use athena::rng_api::{RngApi, RngError, RngResult};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Why a [`WeightedIndex`] could not be built or updated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WeightError {
+    /// No weights were given.
+    NoItem,
+    /// The weight at `index` is negative, NaN or infinite.
+    InvalidWeight { index: usize },
+    /// Every weight is 0, so nothing can be chosen.
+    AllWeightsZero,
+    /// The weights add up to more than `f64::MAX`.
+    TotalOverflow,
+    /// An update referred to an index past the end of the weights.
+    IndexOutOfBounds { index: usize },
+}
+
+impl Display for WeightError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            WeightError::NoItem => write!(f, "No weights were given!"),
+            WeightError::InvalidWeight { index } => {
+                write!(f, "Weight {index} is not a finite, non-negative number!")
+            }
+            WeightError::AllWeightsZero => write!(f, "All weights are zero!"),
+            WeightError::TotalOverflow => write!(f, "The total weight is too large!"),
+            WeightError::IndexOutOfBounds { index } => {
+                write!(f, "Index {index} is out of bounds!")
+            }
+        }
+    }
+}
+
+impl Error for WeightError {}
+
+impl From<WeightError> for RngError {
+    fn from(e: WeightError) -> Self {
+        RngError::Generic(e.to_string())
+    }
+}
+
+/// A weight accepted by [`WeightedIndex`]: any primitive integer or float.
+///
+/// Weights are converted to `f64`, so integers above `2^53` lose precision.
+pub trait Weight: Copy {
+    /// The weight as an `f64`.
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_weight {
+    ($($t:ty),*) => {$(
+        impl Weight for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*};
+}
+
+impl_weight!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+);
+
+/// Chooses indices with probability proportional to their weight, in `O(1)` per sample.
+///
+/// Building the table takes `O(n)`; every sample then costs one bounded integer, one
+/// uniform float and a comparison. Updating weights rebuilds the table in `O(n)`.
+///
+/// ## Example
+/// ```
+/// use tyche::{Tyche, WeightedIndex};
+///
+/// let loot = ["common", "rare", "legendary"];
+/// let table = WeightedIndex::new([90u32, 9, 1]).unwrap();
+/// let mut tyche = Tyche::new().unwrap();
+/// let drop = loot[table.sample(&mut tyche).unwrap()];
+/// assert!(loot.contains(&drop));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightedIndex {
+    weights: Vec<f64>,
+    total: f64,
+    /// Probability of keeping the drawn column instead of switching to its alias.
+    keep: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl WeightedIndex {
+    /// Build the alias table for `weights`.
+    ///
+    /// ## Errors
+    /// Returns `WeightError` if there are no weights, a weight is negative, NaN or infinite,
+    /// all weights are 0 or their total overflows.
+    pub fn new<I>(weights: I) -> Result<Self, WeightError>
+    where
+        I: IntoIterator,
+        I::Item: Weight,
+    {
+        let weights = weights
+            .into_iter()
+            .map(Weight::to_f64)
+            .collect::<Vec<f64>>();
+        let mut table = WeightedIndex {
+            weights,
+            total: 0.0,
+            keep: Vec::new(),
+            alias: Vec::new(),
+        };
+        table.rebuild()?;
+        Ok(table)
+    }
+
+    /// Sample an index.
+    ///
+    /// ## Errors
+    /// Returns `RngError` if the generator fails.
+    pub fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<usize> {
+        let column = uniform::usize_below(rng, self.keep.len())?;
+        if rng.random_unit_f64()? < self.keep[column] {
+            Ok(column)
+        } else {
+            Ok(self.alias[column])
+        }
+    }
+
+    /// Replace the weights at the given indices and rebuild the table.
+    ///
+    /// The update is all or nothing: if any entry is invalid, the table is left unchanged.
+    ///
+    /// ## Errors
+    /// Returns `WeightError` if an index is out of bounds, a weight is invalid, or all
+    /// weights would be 0 or overflow.
+    pub fn update_weights<W: Weight>(&mut self, updates: &[(usize, W)]) -> Result<(), WeightError> {
+        let mut updated = self.clone();
+        for &(index, weight) in updates {
+            match updated.weights.get_mut(index) {
+                Some(w) => *w = weight.to_f64(),
+                None => return Err(WeightError::IndexOutOfBounds { index }),
+            }
+        }
+        updated.rebuild()?;
+        *self = updated;
+        Ok(())
+    }
+
+    /// Replace the weight at `index` and rebuild the table.
+    ///
+    /// ## Errors
+    /// Returns `WeightError` if `index` is out of bounds, the weight is invalid, or all
+    /// weights would be 0 or overflow.
+    pub fn update_weight<W: Weight>(&mut self, index: usize, weight: W) -> Result<(), WeightError> {
+        self.update_weights(&[(index, weight)])
+    }
+
+    /// The weight at `index`, or `None` if out of bounds.
+    pub fn weight(&self, index: usize) -> Option<f64> {
+        self.weights.get(index).copied()
+    }
+
+    /// The sum of all weights.
+    pub fn total_weight(&self) -> f64 {
+        self.total
+    }
+
+    /// The number of weights, including those that are 0.
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Always `false`, a `WeightedIndex` has at least one weight.
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Validate the weights and build the table with Vose's variant of the alias method.
+    ///
+    /// Every column is filled to the average weight: an underfull column keeps its own index
+    /// with probability `weight / average` and is topped up by an overfull one, its alias.
+    fn rebuild(&mut self) -> Result<(), WeightError> {
+        if self.weights.is_empty() {
+            return Err(WeightError::NoItem);
+        }
+        let mut total = 0.0;
+        for (index, &w) in self.weights.iter().enumerate() {
+            if !(w.is_finite() && w >= 0.0) {
+                return Err(WeightError::InvalidWeight { index });
+            }
+            total += w;
+        }
+        if !total.is_finite() {
+            return Err(WeightError::TotalOverflow);
+        }
+        if total == 0.0 {
+            return Err(WeightError::AllWeightsZero);
+        }
+
+        let n = self.weights.len();
+        let mut scaled = self
+            .weights
+            .iter()
+            .map(|&w| w / total * n as f64)
+            .collect::<Vec<f64>>();
+        let mut keep = vec![1.0; n];
+        let mut alias = (0..n).collect::<Vec<usize>>();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..n).partition(|&i| scaled[i] < 1.0);
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            keep[s] = scaled[s];
+            alias[s] = l;
+            scaled[l] -= 1.0 - scaled[s];
+            if scaled[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // Whatever is left is full up to rounding error
+        for i in small.into_iter().chain(large) {
+            keep[i] = 1.0;
+        }
+
+        self.total = total;
+        self.keep = keep;
+        self.alias = alias;
+        Ok(())
+    }
+}
+
+impl Distribution<usize> for WeightedIndex {
+    fn sample<R: RngApi + ?Sized>(&self, rng: &mut R) -> RngResult<usize> {
+        WeightedIndex::sample(self, rng)
+    }
+}